@@ -5,6 +5,10 @@ use eframe::wasm_bindgen::JsCast as _;
 use eframe::{CreationContext, Frame};
 use egui::{CentralPanel, Hyperlink};
 use egui_theme_switch::global_theme_switch;
+#[cfg(target_os = "linux")]
+use egui_theme_switch::system_theme::{self, PriorityChain};
+#[cfg(target_os = "linux")]
+use std::sync::Arc;
 
 mod auto_viewport_theme;
 
@@ -72,16 +76,47 @@ fn main() {
 }
 
 #[derive(Debug)]
-struct ThemeSwitchDemoApp;
+struct ThemeSwitchDemoApp {
+    #[cfg(target_os = "linux")]
+    system_theme: Arc<PriorityChain>,
+}
 
 impl ThemeSwitchDemoApp {
     fn new(cc: &CreationContext) -> Self {
         auto_viewport_theme::register(&cc.egui_ctx);
-        Self
+        Self {
+            #[cfg(target_os = "linux")]
+            system_theme: register_linux_system_theme(&cc.egui_ctx),
+        }
     }
 }
 
+/// Eframe doesn't follow the system theme on Linux.
+/// See: <https://github.com/rust-windowing/winit/issues/1549>
+#[cfg(target_os = "linux")]
+fn register_linux_system_theme(ctx: &egui::Context) -> Arc<PriorityChain> {
+    use egui_theme_switch::system_theme::{
+        DesktopSettingsFiles, EnvironmentVariable, GSettings, XdgPortal,
+    };
+
+    let source = Arc::new(
+        PriorityChain::new()
+            .with(EnvironmentVariable::gtk_theme())
+            .with(XdgPortal::new())
+            .with(GSettings::new())
+            .with(DesktopSettingsFiles::new()),
+    );
+    system_theme::register(ctx, source.clone());
+    system_theme::follow_accent_color(ctx);
+    source
+}
+
 impl eframe::App for ThemeSwitchDemoApp {
+    #[cfg(target_os = "linux")]
+    fn raw_input_hook(&mut self, _ctx: &egui::Context, raw_input: &mut egui::RawInput) {
+        system_theme::feed(raw_input, &self.system_theme);
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut Frame) {
         CentralPanel::default().show(ctx, |ui| {
             ui.style_mut().spacing.interact_size *= 1.5;
@@ -0,0 +1,46 @@
+//! Renders the previews shown in the docs to `doc/dark.png` and `doc/light.png`.
+//!
+//! ```sh
+//! cargo run --example update_previews --features headless
+//! python3 doc/update-rustdoc.py
+//! ```
+
+use egui::epaint::ColorImage;
+use egui::{Theme, ThemePreference};
+use egui_theme_switch::headless::Renderer;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+
+fn main() -> io::Result<()> {
+    let previews = [
+        ("doc/dark.png", Theme::Dark, ThemePreference::Dark),
+        ("doc/light.png", Theme::Light, ThemePreference::Light),
+    ];
+    for (path, theme, preference) in previews {
+        let image = Renderer::new(theme)
+            .pixels_per_point(4.0)
+            .render_switch(preference);
+        write_png(Path::new(path), &image)?;
+        println!("Wrote {path}");
+    }
+    Ok(())
+}
+
+fn write_png(path: &Path, image: &ColorImage) -> io::Result<()> {
+    let [width, height] = image.size;
+    let mut encoder =
+        png::Encoder::new(BufWriter::new(File::create(path)?), width as _, height as _);
+    // The canvas is opaque.
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(png::Compression::Best);
+    let mut writer = encoder.write_header()?;
+    let data: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|pixel| [pixel.r(), pixel.g(), pixel.b()])
+        .collect();
+    writer.write_image_data(&data)?;
+    Ok(())
+}
@@ -1,82 +1,276 @@
+//! Circular and elliptical arcs, pie slices and annular sectors.
+//!
+//! Angles are in radians and follow [`Vec2::angled`]: zero points to the right
+//! and positive angles go clockwise on screen (egui's y axis points down).
+//! Arcs are either approximated with cubic béziers or flattened into points,
+//! in both cases they deviate from the true curve by at most their `max_error` (in points).
+//!
+//! ```
+//! use egui::emath::pos2;
+//! use egui::epaint::{Color32, Stroke};
+//! use egui_theme_switch::arc::{AnnularSector, EllipticalArc, PieSlice};
+//! use std::f32::consts::PI;
+//!
+//! # egui::__run_test_ui(|ui| {
+//! let painter = ui.painter();
+//! let arc = EllipticalArc::circle(pos2(50.0, 50.0), 20.0, 0.0..=PI).with_max_error(0.05);
+//! painter.add(arc.to_path(Stroke::new(2.0, Color32::WHITE)));
+//! painter.add(PieSlice::new(arc).to_mesh(Color32::RED));
+//! let sector = AnnularSector::new(pos2(50.0, 50.0), 10.0..=20.0, PI..=1.5 * PI);
+//! painter.add(sector.to_mesh(Color32::BLUE));
+//! # });
+//! ```
+
 use egui::emath::{vec2, Pos2, Vec2};
-use egui::epaint::{Color32, CubicBezierShape, Stroke};
+use egui::epaint::{Color32, CubicBezierShape, Mesh, PathShape, Stroke};
 use std::f32::consts::FRAC_PI_2;
 use std::ops::RangeInclusive;
 
-pub(crate) fn approximate_with_beziers(
-    center: impl Into<Pos2>,
-    radius: impl Into<f32>,
-    range: impl Into<RangeInclusive<f32>>,
-    fill: impl Into<Color32>,
-    stroke: impl Into<Stroke>,
-) -> impl Iterator<Item = CubicBezierShape> + Clone {
-    let fill = fill.into();
-    let stroke = stroke.into();
-    approximate_with_beziers_impl(center.into(), radius.into(), range.into())
-        .map(move |p| CubicBezierShape::from_points_stroke(p, false, fill, stroke))
-}
+/// The default for [`EllipticalArc::max_error`], small enough to be invisible at common scale factors.
+pub const DEFAULT_MAX_ERROR: f32 = 0.1;
+
+const QUARTER_TURN: f32 = FRAC_PI_2;
+const MAX_SEGMENTS: usize = 1024;
 
-// Implementation based on:
-// Riškus, Aleksas. (2006). Approximation of a cubic bezier curve by circular arcs and vice versa.
-// Information Technology and Control. 35.
-
-fn approximate_with_beziers_impl(
-    center: Pos2,
-    radius: f32,
-    range: RangeInclusive<f32>,
-) -> impl Iterator<Item = [Pos2; 4]> + Clone {
-    QuarterTurnsIter(Some(range))
-        .map(move |r| approximate_with_bezier(center, radius, *r.start(), *r.end()))
+/// An arc of an axis-aligned ellipse, from `start_angle` to `end_angle`.
+///
+/// For ellipses the angles are parametric, i.e. the point at `angle` is
+/// `center + vec2(radius.x * angle.cos(), radius.y * angle.sin())`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EllipticalArc {
+    pub center: Pos2,
+    pub radius: Vec2,
+    pub start_angle: f32,
+    /// Can be smaller than `start_angle` to go counter-clockwise.
+    pub end_angle: f32,
+    /// How far the approximation may deviate from the true arc.
+    pub max_error: f32,
 }
 
-fn approximate_with_bezier(center: Pos2, radius: f32, start: f32, end: f32) -> [Pos2; 4] {
-    let p1 = center + radius * Vec2::angled(start);
-    let p4 = center + radius * Vec2::angled(end);
+impl EllipticalArc {
+    pub fn circle(center: Pos2, radius: f32, angles: RangeInclusive<f32>) -> Self {
+        Self::ellipse(center, Vec2::splat(radius), angles)
+    }
+
+    pub fn ellipse(center: Pos2, radius: Vec2, angles: RangeInclusive<f32>) -> Self {
+        let (start_angle, end_angle) = angles.into_inner();
+        Self {
+            center,
+            radius,
+            start_angle,
+            end_angle,
+            max_error: DEFAULT_MAX_ERROR,
+        }
+    }
+
+    pub fn with_max_error(mut self, max_error: f32) -> Self {
+        self.max_error = max_error;
+        self
+    }
+
+    pub fn sweep(&self) -> f32 {
+        self.end_angle - self.start_angle
+    }
+
+    pub fn point_at(&self, angle: f32) -> Pos2 {
+        self.center + self.radius * Vec2::angled(angle)
+    }
+
+    /// Approximates the arc with as few cubic béziers as the `max_error` allows.
+    /// Each bézier is given by its four control points.
+    pub fn to_cubic_beziers(&self) -> Vec<[Pos2; 4]> {
+        let radius = self.radius.max_elem();
+        let sweep = self.sweep();
+        if sweep == 0.0 {
+            return Vec::new();
+        }
+        let quarter_turns = (sweep.abs() / QUARTER_TURN).ceil().max(1.0) as usize;
+        let segments = (quarter_turns..MAX_SEGMENTS)
+            .find(|&n| radius * bezier_error(sweep / n as f32) <= self.max_error)
+            .unwrap_or(MAX_SEGMENTS);
+        self.segment_angles(segments)
+            .map(|(start, end)| self.approximate_with_bezier(start, end))
+            .collect()
+    }
+
+    /// The béziers from [`EllipticalArc::to_cubic_beziers`] as shapes.
+    pub fn to_bezier_shapes(&self, stroke: impl Into<Stroke>) -> Vec<CubicBezierShape> {
+        let stroke = stroke.into();
+        self.to_cubic_beziers()
+            .into_iter()
+            .map(|p| CubicBezierShape::from_points_stroke(p, false, Color32::TRANSPARENT, stroke))
+            .collect()
+    }
 
-    let a = p1 - center;
-    let b = p4 - center;
-    let q1 = a.length_sq();
-    let q2 = q1 + a.dot(b);
-    let k2 = (4.0 / 3.0) * ((2.0 * q1 * q2).sqrt() - q2) / (a.x * b.y - a.y * b.x);
+    /// Points on the arc, close enough together that the
+    /// lines between them stay within `max_error` of the arc.
+    pub fn points(&self) -> Vec<Pos2> {
+        let segments = self.flattened_segments();
+        (0..=segments)
+            .map(|n| self.point_at(self.angle_at(n, segments)))
+            .collect()
+    }
+
+    pub fn to_path(&self, stroke: impl Into<Stroke>) -> PathShape {
+        PathShape::line(self.points(), stroke.into())
+    }
 
-    let p2 = center + vec2(a.x - k2 * a.y, a.y + k2 * a.x);
-    let p3 = center + vec2(b.x + k2 * b.y, b.y - k2 * b.x);
+    /// The stroke as a filled band around the arc.
+    ///
+    /// Unlike [`EllipticalArc::to_path`] the mesh is not anti-aliased,
+    /// and for ellipses the band's width is only approximately even.
+    pub fn to_stroke_mesh(&self, stroke: impl Into<Stroke>) -> Mesh {
+        let stroke = stroke.into();
+        let half_width = Vec2::splat(stroke.width / 2.0);
+        let outer = Self {
+            radius: self.radius + half_width,
+            ..*self
+        };
+        let inner = Self {
+            radius: (self.radius - half_width).max(Vec2::ZERO),
+            ..*self
+        };
+        band_mesh(&outer, &inner, stroke.color)
+    }
+
+    fn flattened_segments(&self) -> usize {
+        let radius = self.radius.max_elem();
+        if radius <= self.max_error {
+            return 1;
+        }
+        // The sagitta of a chord spanning `max_angle` is exactly `max_error`.
+        let max_angle = 2.0 * (1.0 - self.max_error / radius).acos();
+        ((self.sweep().abs() / max_angle).ceil() as usize).clamp(1, MAX_SEGMENTS)
+    }
+
+    fn angle_at(&self, n: usize, segments: usize) -> f32 {
+        self.start_angle + self.sweep() * (n as f32 / segments as f32)
+    }
+
+    fn segment_angles(&self, segments: usize) -> impl Iterator<Item = (f32, f32)> + '_ {
+        (0..segments).map(move |n| (self.angle_at(n, segments), self.angle_at(n + 1, segments)))
+    }
 
-    [p1, p2, p3, p4]
+    // Implementation based on:
+    // Riškus, Aleksas. (2006). Approximation of a cubic bezier curve by circular arcs and vice versa.
+    // Information Technology and Control. 35.
+    //
+    // The bézier approximates the unit circle, scaling it afterwards
+    // gives us ellipses because béziers are affine invariant.
+    fn approximate_with_bezier(&self, start: f32, end: f32) -> [Pos2; 4] {
+        let a = Vec2::angled(start);
+        let b = Vec2::angled(end);
+        let q1 = a.length_sq();
+        let q2 = q1 + a.dot(b);
+        let k2 = (4.0 / 3.0) * ((2.0 * q1 * q2).sqrt() - q2) / (a.x * b.y - a.y * b.x);
+
+        let p2 = vec2(a.x - k2 * a.y, a.y + k2 * a.x);
+        let p3 = vec2(b.x + k2 * b.y, b.y - k2 * b.x);
+
+        [a, p2, p3, b].map(|p| self.center + self.radius * p)
+    }
+}
+
+/// The maximum radial error of the bézier approximation of a unit circle's arc
+/// spanning `sweep` radians.
+///
+/// The control points are at a distance of `4/3 tan(sweep/4)` from the end points,
+/// which gives an error of `2/27 sin⁶(sweep/4) / cos²(sweep/4)`.
+pub fn bezier_error(sweep: f32) -> f32 {
+    let (sin, cos) = (sweep.abs() / 4.0).sin_cos();
+    (2.0 / 27.0) * sin.powi(6) / cos.powi(2)
 }
 
-// We can approximate at most one quadrant of the circle
-// so we divide it up into individual chunks that we then approximate
-// using bezier curves.
-#[derive(Clone)]
-struct QuarterTurnsIter(Option<RangeInclusive<f32>>);
+/// The area between an arc and its center.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PieSlice {
+    pub arc: EllipticalArc,
+}
 
-const QUARTER_TURN: f32 = FRAC_PI_2;
+impl PieSlice {
+    pub fn new(arc: EllipticalArc) -> Self {
+        Self { arc }
+    }
 
-impl Iterator for QuarterTurnsIter {
-    type Item = RangeInclusive<f32>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let (start, end) = self.0.clone()?.into_inner();
-        let distance = end - start;
-        if distance < QUARTER_TURN {
-            self.0 = None;
-            Some(start..=end)
-        } else {
-            let new_start = start + (QUARTER_TURN * distance.signum());
-            self.0 = Some(new_start..=end);
-            Some(start..=new_start)
+    /// The outline, starting and ending at the center.
+    pub fn points(&self) -> Vec<Pos2> {
+        let mut points = vec![self.arc.center];
+        points.extend(self.arc.points());
+        points
+    }
+
+    /// The fill is only drawn correctly if the slice is convex
+    /// (i.e. it spans at most half a turn), use [`PieSlice::to_mesh`] otherwise.
+    pub fn to_path(&self, fill: impl Into<Color32>, stroke: impl Into<Stroke>) -> PathShape {
+        PathShape::convex_polygon(self.points(), fill, stroke.into())
+    }
+
+    /// The slice as a triangle fan, this works for any angle.
+    pub fn to_mesh(&self, fill: impl Into<Color32>) -> Mesh {
+        let fill = fill.into();
+        let mut mesh = Mesh::default();
+        for point in self.points() {
+            mesh.colored_vertex(point, fill);
         }
+        for n in 1..mesh.vertices.len().saturating_sub(1) as u32 {
+            mesh.add_triangle(0, n, n + 1);
+        }
+        mesh
     }
+}
 
-    fn size_hint(&self) -> (usize, Option<usize>) {
-        if let Some((start, end)) = self.0.clone().map(|x| x.into_inner()) {
-            let turns = (start - end).abs() / QUARTER_TURN;
-            let lower = turns.floor() as usize;
-            let upper = turns.ceil() as usize;
-            (lower, Some(upper))
-        } else {
-            (0, None)
+/// The area between two concentric arcs spanning the same angles, i.e. a ring segment.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnnularSector {
+    pub inner: EllipticalArc,
+    pub outer: EllipticalArc,
+}
+
+impl AnnularSector {
+    pub fn new(center: Pos2, radii: RangeInclusive<f32>, angles: RangeInclusive<f32>) -> Self {
+        let (inner, outer) = radii.into_inner();
+        Self {
+            inner: EllipticalArc::circle(center, inner, angles.clone()),
+            outer: EllipticalArc::circle(center, outer, angles),
+        }
+    }
+
+    pub fn with_max_error(self, max_error: f32) -> Self {
+        Self {
+            inner: self.inner.with_max_error(max_error),
+            outer: self.outer.with_max_error(max_error),
         }
     }
+
+    /// The outline: along the outer arc and back along the inner arc.
+    pub fn points(&self) -> Vec<Pos2> {
+        let mut points = self.outer.points();
+        points.extend(self.inner.points().into_iter().rev());
+        points
+    }
+
+    /// The sector is not convex, so the path can only be used for strokes.
+    pub fn to_path(&self, stroke: impl Into<Stroke>) -> PathShape {
+        PathShape::closed_line(self.points(), stroke.into())
+    }
+
+    pub fn to_mesh(&self, fill: impl Into<Color32>) -> Mesh {
+        band_mesh(&self.outer, &self.inner, fill.into())
+    }
+}
+
+/// Connects the points at the same relative position on both arcs with a triangle strip.
+pub(crate) fn band_mesh(outer: &EllipticalArc, inner: &EllipticalArc, color: Color32) -> Mesh {
+    let segments = outer.flattened_segments();
+    let mut mesh = Mesh::default();
+    for n in 0..=segments {
+        mesh.colored_vertex(outer.point_at(outer.angle_at(n, segments)), color);
+        mesh.colored_vertex(inner.point_at(inner.angle_at(n, segments)), color);
+    }
+    for n in 0..segments as u32 {
+        let i = 2 * n;
+        mesh.add_triangle(i, i + 1, i + 2);
+        mesh.add_triangle(i + 1, i + 3, i + 2);
+    }
+    mesh
 }
@@ -0,0 +1,41 @@
+use super::icons::IconStyle;
+use egui::emath::{pos2, Pos2};
+use egui::epaint::{Color32, PathShape, Stroke};
+use egui::Painter;
+
+/// Draws the letter "A".
+pub(crate) fn automatic(
+    painter: &Painter,
+    center: Pos2,
+    radius: f32,
+    color: Color32,
+    style: IconStyle,
+    line_width: f32,
+) {
+    // The filled letter is bold.
+    let line_width = match style {
+        IconStyle::Filled => line_width.max(radius / 4.),
+        IconStyle::Outline => line_width,
+    };
+    let stroke = Stroke::new(line_width, color);
+
+    let half_width = 0.7 * radius;
+    let bottom = center.y + 0.85 * radius;
+    // The joint at the apex is mitered, so it sticks out above the apex.
+    let top = center.y - 0.85 * radius + line_width;
+    let apex = pos2(center.x, top);
+    let left = pos2(center.x - half_width, bottom);
+    let right = pos2(center.x + half_width, bottom);
+    painter.add(PathShape::line(vec![left, apex, right], stroke));
+
+    let bar_y = center.y + 0.3 * radius;
+    let t = (bar_y - top) / (bottom - top);
+    let bar_half_width = t * half_width;
+    painter.line_segment(
+        [
+            pos2(center.x - bar_half_width, bar_y),
+            pos2(center.x + bar_half_width, bar_y),
+        ],
+        stroke,
+    );
+}
@@ -1,36 +1,129 @@
-use super::rotated_rect::draw_rotated_rect;
+use super::arc::EllipticalArc;
+use super::icons::IconStyle;
+use super::rotated_rect::RotatedRect;
 use crate::Painter;
-use egui::emath::{vec2, Pos2, Rect, Rot2};
-use egui::epaint::{Color32, CornerRadius};
-use std::f32::consts::TAU;
+use egui::emath::{vec2, Pos2, Rect, Rot2, Vec2};
+use egui::epaint::{Color32, CornerRadiusF32, PathShape, Stroke};
+use std::f32::consts::{FRAC_PI_2, TAU};
 
-pub(crate) fn cogwheel(painter: &Painter, center: Pos2, radius: f32, color: Color32) {
+const COGS: usize = 8;
+
+pub(crate) fn cogwheel(
+    painter: &Painter,
+    center: Pos2,
+    radius: f32,
+    color: Color32,
+    style: IconStyle,
+    line_width: f32,
+) {
     let inner_radius = 0.3 * radius;
     let outer_radius = 0.8 * radius;
     let thickness = 0.3 * radius;
 
-    painter.circle(
-        center,
-        inner_radius + thickness / 2.,
-        Color32::TRANSPARENT,
-        (thickness, color),
-    );
-
-    let cogs = 8;
     let cog_width = radius / 2.5;
-    let cog_rounding = (radius / 16.) as u8;
+    let cog_rounding = radius / 16.;
     let cog_length = radius - outer_radius + thickness / 2.;
 
-    for n in 0..cogs {
-        let cog_center = center - vec2(0., outer_radius + cog_length / 2. - thickness / 2.);
-        let cog_size = vec2(cog_width, cog_length);
-        let rotation = Rot2::from_angle(TAU / cogs as f32 * n as f32);
-        let rect = Rect::from_center_size(cog_center, cog_size);
-        let rounding = CornerRadius {
-            nw: cog_rounding,
-            ne: cog_rounding,
-            ..Default::default()
-        };
-        draw_rotated_rect(painter, rect, rounding, color, rotation, center);
+    match style {
+        IconStyle::Filled => {
+            painter.circle_stroke(center, inner_radius + thickness / 2., (thickness, color));
+
+            for n in 0..COGS {
+                let cog_center = center - vec2(0., outer_radius + cog_length / 2. - thickness / 2.);
+                let cog_size = vec2(cog_width, cog_length);
+                let rotation = Rot2::from_angle(TAU / COGS as f32 * n as f32);
+                let rect = Rect::from_center_size(cog_center, cog_size);
+                let rounding = CornerRadiusF32 {
+                    nw: cog_rounding,
+                    ne: cog_rounding,
+                    ..Default::default()
+                };
+                RotatedRect::new(rect, rounding, rotation, center).paint(
+                    painter,
+                    color,
+                    Stroke::NONE,
+                );
+            }
+        }
+        IconStyle::Outline => {
+            let stroke = Stroke::new(line_width, color);
+            painter.circle_stroke(center, inner_radius + line_width / 2., stroke);
+
+            // The stroke is centered on the outline, so we shrink
+            // the outline to keep the outlined cogwheel the same size.
+            let inset = line_width / 2.;
+            let outline = Outline {
+                center,
+                ring_radius: inner_radius + thickness - inset,
+                tip_radius: radius - inset,
+                cog_half_width: cog_width / 2. - inset,
+                cog_rounding: (cog_rounding - inset).max(0.0),
+                tolerance: 0.1 / painter.pixels_per_point(),
+            };
+            painter.add(PathShape::closed_line(outline.points(), stroke));
+        }
+    }
+}
+
+/// The outer edge of the ring with the cogs on it, as one line.
+struct Outline {
+    center: Pos2,
+    ring_radius: f32,
+    tip_radius: f32,
+    cog_half_width: f32,
+    cog_rounding: f32,
+    tolerance: f32,
+}
+
+impl Outline {
+    fn points(&self) -> Vec<Pos2> {
+        // Where the sides of a cog meet the ring, relative to the cog's direction.
+        let side_angle = (self.cog_half_width / self.ring_radius).asin();
+        let side_start = self.ring_radius * side_angle.cos();
+
+        let mut points = Vec::new();
+        for n in 0..COGS {
+            // The first cog points up, like in the filled cogwheel.
+            let angle = -FRAC_PI_2 + TAU / COGS as f32 * n as f32;
+            let along = Vec2::angled(angle);
+            let across = Vec2::angled(angle + FRAC_PI_2);
+            let corner = |side: f32| {
+                self.center
+                    + (self.tip_radius - self.cog_rounding) * along
+                    + side * (self.cog_half_width - self.cog_rounding) * across
+            };
+
+            points.push(self.center + side_start * along - self.cog_half_width * across);
+            points.extend(self.arc(corner(-1.0), self.cog_rounding, angle - FRAC_PI_2, angle));
+            points.extend(self.arc(corner(1.0), self.cog_rounding, angle, angle + FRAC_PI_2));
+            points.push(self.center + side_start * along + self.cog_half_width * across);
+
+            // Along the ring to the next cog.
+            let next = angle + TAU / COGS as f32;
+            let ring = self.arc(
+                self.center,
+                self.ring_radius,
+                angle + side_angle,
+                next - side_angle,
+            );
+            // The last arc ends where the first cog starts.
+            let ring = if n + 1 == COGS {
+                &ring[..ring.len() - 1]
+            } else {
+                &ring[..]
+            };
+            points.extend_from_slice(ring);
+        }
+        points.dedup_by(|a, b| a.distance(*b) < self.tolerance);
+        points
+    }
+
+    fn arc(&self, center: Pos2, radius: f32, start: f32, end: f32) -> Vec<Pos2> {
+        if radius <= 0.0 {
+            return vec![center];
+        }
+        EllipticalArc::circle(center, radius, start..=end)
+            .with_max_error(self.tolerance)
+            .points()
     }
 }
@@ -0,0 +1,34 @@
+use super::arc::{EllipticalArc, PieSlice};
+use super::icons::IconStyle;
+use egui::emath::{vec2, Pos2};
+use egui::epaint::{Color32, Stroke};
+use egui::Painter;
+use std::f32::consts::FRAC_PI_2;
+
+/// Draws a circle with its right half filled.
+pub(crate) fn contrast(
+    painter: &Painter,
+    center: Pos2,
+    radius: f32,
+    color: Color32,
+    style: IconStyle,
+    line_width: f32,
+) {
+    let stroke = Stroke::new(line_width, color);
+    // The stroke is centered on the circle, this keeps it inside of `radius`.
+    let ring_radius = radius - line_width / 2.;
+    painter.circle_stroke(center, ring_radius, stroke);
+
+    match style {
+        IconStyle::Filled => {
+            let tolerance = 0.1 / painter.pixels_per_point();
+            let half = EllipticalArc::circle(center, ring_radius, -FRAC_PI_2..=FRAC_PI_2)
+                .with_max_error(tolerance);
+            painter.add(PieSlice::new(half).to_path(color, Stroke::NONE));
+        }
+        IconStyle::Outline => {
+            let half_height = vec2(0., ring_radius);
+            painter.line_segment([center - half_height, center + half_height], stroke);
+        }
+    }
+}
@@ -0,0 +1,314 @@
+//! Renders the switch to an image on the CPU, without a GPU or a window.
+//!
+//! This is what the previews in the docs are made with,
+//! it's also handy for pixel-diff regression tests in CI.
+//!
+//! ```
+//! use egui::{Theme, ThemePreference};
+//! use egui_theme_switch::headless::Renderer;
+//!
+//! let image = Renderer::new(Theme::Dark)
+//!     .pixels_per_point(2.0)
+//!     .render_switch(ThemePreference::Dark);
+//! assert_eq!(image.size, [400, 80]);
+//! ```
+
+use crate::ThemeSwitch;
+use egui::emath::{vec2, Align2, Pos2, Rect, Vec2};
+use egui::epaint::{
+    ClippedPrimitive, Color32, ColorImage, ImageData, ImageDelta, Mesh, Primitive, TextureId,
+    Vertex,
+};
+use egui::{
+    Area, CentralPanel, Context, Frame, Id, RawInput, TextureFilter, Theme, ThemePreference, Ui,
+    ViewportId,
+};
+use std::collections::HashMap;
+
+/// Renders ui contents centered on a canvas filled with the theme's panel colour.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Renderer {
+    theme: Theme,
+    size: Vec2,
+    pixels_per_point: f32,
+}
+
+impl Renderer {
+    /// A canvas of 200 × 40 points at one pixel per point.
+    pub fn new(theme: Theme) -> Self {
+        Self {
+            theme,
+            size: vec2(200.0, 40.0),
+            pixels_per_point: 1.0,
+        }
+    }
+
+    /// The size of the canvas in points.
+    pub fn size(mut self, size: Vec2) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn pixels_per_point(mut self, pixels_per_point: f32) -> Self {
+        self.pixels_per_point = pixels_per_point;
+        self
+    }
+
+    /// Renders a [`ThemeSwitch`] with `preference` selected.
+    pub fn render_switch(&self, mut preference: ThemePreference) -> ColorImage {
+        self.render(|ui| {
+            ui.add(ThemeSwitch::new(&mut preference));
+        })
+    }
+
+    /// Runs a fresh [`Context`] and rasterizes the result.
+    ///
+    /// Animations start out at their target value, so the
+    /// image shows what the contents look like once they've settled.
+    /// Text only shows up when egui's `default_fonts` feature is enabled.
+    pub fn render(&self, mut add_contents: impl FnMut(&mut Ui)) -> ColorImage {
+        let ctx = Context::default();
+        ctx.set_theme(self.theme);
+        let mut input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, self.size)),
+            ..Default::default()
+        };
+        input
+            .viewports
+            .entry(ViewportId::ROOT)
+            .or_default()
+            .native_pixels_per_point = Some(self.pixels_per_point);
+
+        let mut rasterizer = Rasterizer::new(self.size, self.pixels_per_point);
+        // The area is measured in the first pass and can only be centered in the second one.
+        let mut shapes = Vec::new();
+        for _ in 0..2 {
+            let output = ctx.run(input.clone(), |ctx| {
+                CentralPanel::default()
+                    .frame(Frame::NONE.fill(ctx.style().visuals.panel_fill))
+                    .show(ctx, |_| {});
+                Area::new(Id::new("egui_theme_switch::headless"))
+                    .anchor(Align2::CENTER_CENTER, Vec2::ZERO)
+                    .fade_in(false)
+                    .show(ctx, &mut add_contents);
+            });
+            for (id, delta) in output.textures_delta.set {
+                rasterizer.set_texture(id, delta);
+            }
+            shapes = output.shapes;
+        }
+        rasterizer.paint(&ctx.tessellate(shapes, ctx.pixels_per_point()));
+        rasterizer.finish()
+    }
+}
+
+/// A minimal software version of what egui's GPU backends do:
+/// textured, vertex-coloured triangles blended with premultiplied alpha in gamma space.
+struct Rasterizer {
+    size: [usize; 2],
+    pixels_per_point: f32,
+    /// Premultiplied RGBA in `0.0..=1.0`.
+    pixels: Vec<[f32; 4]>,
+    textures: HashMap<TextureId, Texture>,
+}
+
+struct Texture {
+    size: [usize; 2],
+    pixels: Vec<Color32>,
+    filter: TextureFilter,
+}
+
+impl Rasterizer {
+    fn new(size: Vec2, pixels_per_point: f32) -> Self {
+        let size = (size * pixels_per_point).round();
+        let size = [size.x as usize, size.y as usize];
+        Self {
+            size,
+            pixels_per_point,
+            pixels: vec![[0.0; 4]; size[0] * size[1]],
+            textures: HashMap::new(),
+        }
+    }
+
+    fn set_texture(&mut self, id: TextureId, delta: ImageDelta) {
+        let size = delta.image.size();
+        let pixels: Vec<Color32> = match &delta.image {
+            ImageData::Color(image) => image.pixels.clone(),
+            ImageData::Font(image) => image.srgba_pixels(None).collect(),
+        };
+        match delta.pos {
+            None => {
+                let filter = delta.options.magnification;
+                self.textures.insert(
+                    id,
+                    Texture {
+                        size,
+                        pixels,
+                        filter,
+                    },
+                );
+            }
+            Some([x, y]) => {
+                if let Some(texture) = self.textures.get_mut(&id) {
+                    for (row, patch) in pixels.chunks_exact(size[0]).enumerate() {
+                        let start = (y + row) * texture.size[0] + x;
+                        texture.pixels[start..start + size[0]].copy_from_slice(patch);
+                    }
+                }
+            }
+        }
+    }
+
+    fn paint(&mut self, primitives: &[ClippedPrimitive]) {
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in primitives
+        {
+            // Callbacks need a GPU.
+            if let Primitive::Mesh(mesh) = primitive {
+                self.paint_mesh(*clip_rect, mesh);
+            }
+        }
+    }
+
+    fn paint_mesh(&mut self, clip_rect: Rect, mesh: &Mesh) {
+        let clip = self.clip_in_pixels(clip_rect);
+        let Some(texture) = self.textures.get(&mesh.texture_id) else {
+            return;
+        };
+        for triangle in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|n| mesh.vertices[triangle[n] as usize]);
+            let vertices = [a, b, c].map(|v| Vertex {
+                pos: (v.pos.to_vec2() * self.pixels_per_point).to_pos2(),
+                ..v
+            });
+            paint_triangle(&mut self.pixels, self.size[0], clip, texture, vertices);
+        }
+    }
+
+    fn clip_in_pixels(&self, clip_rect: Rect) -> [usize; 4] {
+        let clamp = |value: f32, max: usize| {
+            ((value * self.pixels_per_point).round().max(0.0) as usize).min(max)
+        };
+        [
+            clamp(clip_rect.min.x, self.size[0]),
+            clamp(clip_rect.min.y, self.size[1]),
+            clamp(clip_rect.max.x, self.size[0]),
+            clamp(clip_rect.max.y, self.size[1]),
+        ]
+    }
+
+    fn finish(self) -> ColorImage {
+        let pixels = self
+            .pixels
+            .into_iter()
+            .map(|[r, g, b, a]| {
+                let [r, g, b, a] =
+                    [r, g, b, a].map(|c| (c * 255.0).round().clamp(0.0, 255.0) as u8);
+                Color32::from_rgba_premultiplied(r, g, b, a)
+            })
+            .collect();
+        ColorImage {
+            size: self.size,
+            pixels,
+        }
+    }
+}
+
+fn paint_triangle(
+    pixels: &mut [[f32; 4]],
+    width: usize,
+    [min_x, min_y, max_x, max_y]: [usize; 4],
+    texture: &Texture,
+    vertices: [Vertex; 3],
+) {
+    let [a, b, c] = vertices.map(|v| v.pos);
+    let area = edge(a, b, c);
+    if area == 0.0 {
+        return;
+    }
+    // Only the pixels whose centers are inside the triangle are painted.
+    let bounds = Rect::from_points(&[a, b, c]);
+    let x_range = (bounds.min.x.floor().max(min_x as f32) as usize)
+        ..(bounds.max.x.ceil() as usize).min(max_x);
+    let y_range = (bounds.min.y.floor().max(min_y as f32) as usize)
+        ..(bounds.max.y.ceil() as usize).min(max_y);
+
+    for y in y_range {
+        for x in x_range.clone() {
+            let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let weights = [edge(b, c, p), edge(c, a, p), edge(a, b, p)].map(|w| w / area);
+            // A pixel exactly on the edge shared by two triangles is owned by only one of them,
+            // otherwise translucent edges would be painted twice.
+            let owned = weights
+                .iter()
+                .zip([(b, c), (c, a), (a, b)])
+                .all(|(&w, (from, to))| w > 0.0 || (w == 0.0 && is_top_left(from, to, area)));
+            if owned {
+                let color = shade(texture, &vertices, weights);
+                blend(&mut pixels[y * width + x], color);
+            }
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `p`.
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b - a).x * (p - a).y - (b - a).y * (p - a).x
+}
+
+fn is_top_left(from: Pos2, to: Pos2, area: f32) -> bool {
+    let d = (to - from) * area.signum();
+    (d.y == 0.0 && d.x > 0.0) || d.y < 0.0
+}
+
+fn shade(texture: &Texture, vertices: &[Vertex; 3], weights: [f32; 3]) -> [f32; 4] {
+    let mut color = [0.0; 4];
+    let mut uv = Vec2::ZERO;
+    for (vertex, weight) in vertices.iter().zip(weights) {
+        for (c, v) in color.iter_mut().zip(vertex.color.to_array()) {
+            *c += weight * f32::from(v) / 255.0;
+        }
+        uv += weight * vertex.uv.to_vec2();
+    }
+    let texel = texture.sample(uv);
+    [0, 1, 2, 3].map(|n| color[n] * texel[n])
+}
+
+fn blend(dst: &mut [f32; 4], src: [f32; 4]) {
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d = s + *d * (1.0 - src[3]);
+    }
+}
+
+impl Texture {
+    fn sample(&self, uv: Vec2) -> [f32; 4] {
+        let [width, height] = self.size;
+        let pos = uv * vec2(width as f32, height as f32) - Vec2::splat(0.5);
+        match self.filter {
+            TextureFilter::Nearest => self.texel(pos.x.round() as isize, pos.y.round() as isize),
+            TextureFilter::Linear => {
+                let (x, y) = (pos.x.floor(), pos.y.floor());
+                let (tx, ty) = (pos.x - x, pos.y - y);
+                let (x, y) = (x as isize, y as isize);
+                let top = lerp(self.texel(x, y), self.texel(x + 1, y), tx);
+                let bottom = lerp(self.texel(x, y + 1), self.texel(x + 1, y + 1), tx);
+                lerp(top, bottom, ty)
+            }
+        }
+    }
+
+    fn texel(&self, x: isize, y: isize) -> [f32; 4] {
+        let [width, height] = self.size;
+        let x = x.clamp(0, width as isize - 1) as usize;
+        let y = y.clamp(0, height as isize - 1) as usize;
+        self.pixels[y * width + x]
+            .to_array()
+            .map(|c| f32::from(c) / 255.0)
+    }
+}
+
+fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|n| a[n] + (b[n] - a[n]) * t)
+}
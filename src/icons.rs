@@ -0,0 +1,260 @@
+//! The icons used by the theme switch, for use in your own widgets.
+//!
+//! ```
+//! use egui::emath::{pos2, Rect};
+//! use egui::epaint::Color32;
+//! use egui_theme_switch::icons::{Icon, IconStyle};
+//!
+//! # egui::__run_test_ui(|ui| {
+//! let painter = ui.painter();
+//! Icon::Sun.paint(painter, pos2(20.0, 20.0), 10.0, Color32::GOLD, IconStyle::Filled);
+//!
+//! let rect = Rect::from_min_size(pos2(40.0, 10.0), egui::vec2(20.0, 20.0));
+//! Icon::Moon.paint_in_rect(painter, rect, Color32::WHITE, IconStyle::Outline);
+//! # });
+//! ```
+
+use crate::{automatic, cogwheel, contrast, monitor, moon, sun};
+use egui::emath::{Pos2, Rect};
+use egui::epaint::Color32;
+use egui::Painter;
+
+/// One of the glyphs drawn by the theme switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Icon {
+    /// A sun with eight rays, used for the light theme.
+    Sun,
+    /// A crescent moon, used for the dark theme.
+    Moon,
+    /// A cogwheel, used for following the system theme.
+    Cogwheel,
+    /// A computer monitor, an alternative for following the system theme.
+    Monitor,
+    /// A circle with one half filled.
+    Contrast,
+    /// The letter "A", for automatic.
+    Automatic,
+}
+
+/// Whether an icon's shapes are filled or only outlined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum IconStyle {
+    /// Solid shapes, with lines where a glyph has no area to fill.
+    #[default]
+    Filled,
+    /// Only the outlines, drawn with the icon's line width.
+    Outline,
+}
+
+impl Icon {
+    /// Paints the icon so that it fits into a circle with the given `radius`.
+    pub fn paint(
+        self,
+        painter: &Painter,
+        center: Pos2,
+        radius: f32,
+        color: Color32,
+        style: IconStyle,
+    ) {
+        let line_width = self.line_width() * radius;
+        self.paint_with_line_width(painter, center, radius, color, style, line_width);
+    }
+
+    /// Like [`Icon::paint`] but with a custom width for lines and outlines,
+    /// e.g. to give different icons the same line weight.
+    pub fn paint_with_line_width(
+        self,
+        painter: &Painter,
+        center: Pos2,
+        radius: f32,
+        color: Color32,
+        style: IconStyle,
+        line_width: f32,
+    ) {
+        // Thinner lines would fade away.
+        let line_width = line_width.max(1.0 / painter.pixels_per_point());
+        let glyph = match self {
+            Icon::Sun => sun::sun,
+            Icon::Moon => moon::moon,
+            Icon::Cogwheel => cogwheel::cogwheel,
+            Icon::Monitor => monitor::monitor,
+            Icon::Contrast => contrast::contrast,
+            Icon::Automatic => automatic::automatic,
+        };
+        glyph(painter, center, radius, color, style, line_width);
+    }
+
+    /// Paints the icon as large as possible in the center of `rect`.
+    pub fn paint_in_rect(self, painter: &Painter, rect: Rect, color: Color32, style: IconStyle) {
+        let radius = 0.5 * rect.width().min(rect.height());
+        self.paint(painter, rect.center(), radius, color, style);
+    }
+
+    /// The default line width relative to the radius.
+    fn line_width(self) -> f32 {
+        match self {
+            Icon::Moon | Icon::Automatic => 1.0 / 5.0,
+            Icon::Monitor => 1.0 / 6.0,
+            Icon::Sun | Icon::Cogwheel | Icon::Contrast => 1.0 / 8.0,
+        }
+    }
+}
+
+/// The icons shown in a [`ThemeSwitch`](crate::ThemeSwitch).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[non_exhaustive]
+pub enum IconSet {
+    /// A cogwheel, a moon and a sun.
+    #[default]
+    Classic,
+    /// The classic icons, outlined with the same line weight.
+    Minimal,
+    /// A monitor for following the system theme.
+    Device,
+    /// A half filled circle for following the system theme.
+    Contrast,
+    /// An "A" for following the system theme.
+    Automatic,
+}
+
+/// An icon as it's drawn in the switch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Glyph {
+    icon: Icon,
+    style: IconStyle,
+    /// Relative to the radius.
+    line_width: f32,
+}
+
+impl Glyph {
+    fn new(icon: Icon, style: IconStyle) -> Self {
+        Self {
+            icon,
+            style,
+            line_width: icon.line_width(),
+        }
+    }
+
+    fn with_line_width(mut self, line_width: f32) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    pub(crate) fn paint(self, painter: &Painter, center: Pos2, radius: f32, color: Color32) {
+        let line_width = self.line_width * radius;
+        (self.icon).paint_with_line_width(painter, center, radius, color, self.style, line_width);
+    }
+}
+
+impl IconSet {
+    /// The icons for following the system theme, dark and light.
+    pub(crate) fn glyphs(self) -> [Glyph; 3] {
+        let moon = Glyph::new(Icon::Moon, IconStyle::Outline);
+        let sun = Glyph::new(Icon::Sun, IconStyle::Filled);
+        match self {
+            IconSet::Classic => [Glyph::new(Icon::Cogwheel, IconStyle::Filled), moon, sun],
+            IconSet::Minimal => [Icon::Cogwheel, Icon::Moon, Icon::Sun]
+                .map(|icon| Glyph::new(icon, IconStyle::Outline).with_line_width(1.0 / 8.0)),
+            IconSet::Device => [Glyph::new(Icon::Monitor, IconStyle::Outline), moon, sun],
+            IconSet::Contrast => [Glyph::new(Icon::Contrast, IconStyle::Filled), moon, sun],
+            IconSet::Automatic => [Glyph::new(Icon::Automatic, IconStyle::Filled), moon, sun],
+        }
+    }
+}
+
+/// The moon in any phase, e.g. for a lunar calendar.
+///
+/// Only the lit part of the moon is painted, except at new moon
+/// where the moon's outline is drawn instead, in either style.
+/// The phase can be animated smoothly.
+///
+/// ```
+/// use egui::emath::pos2;
+/// use egui::epaint::Color32;
+/// use egui_theme_switch::icons::{IconStyle, MoonPhase};
+///
+/// # egui::__run_test_ui(|ui| {
+/// let painter = ui.painter();
+/// let gibbous = MoonPhase::new(0.75).waning().with_tilt(0.3);
+/// gibbous.paint(painter, pos2(20.0, 20.0), 10.0, Color32::WHITE, IconStyle::Filled);
+///
+/// // A whole lunar cycle in eight steps.
+/// for n in 0..8 {
+///     let moon = MoonPhase::from_cycle(n as f32 / 8.0);
+///     let center = pos2(20.0 + 25.0 * n as f32, 50.0);
+///     moon.paint(painter, center, 10.0, Color32::WHITE, IconStyle::Outline);
+/// }
+/// # });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoonPhase {
+    /// From `0.0` (new moon) over `0.5` (half moon) to `1.0` (full moon).
+    pub phase: f32,
+    pub direction: MoonDirection,
+    /// Clockwise rotation in radians. With no tilt, a waxing moon is lit from the right.
+    pub tilt: f32,
+}
+
+/// Whether the lit part of the moon is growing or shrinking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MoonDirection {
+    #[default]
+    Waxing,
+    Waning,
+}
+
+impl MoonPhase {
+    /// A waxing moon without tilt.
+    pub fn new(phase: f32) -> Self {
+        Self {
+            phase,
+            direction: MoonDirection::Waxing,
+            tilt: 0.0,
+        }
+    }
+
+    /// The moon at a point in the lunar cycle, from `0.0` (new moon)
+    /// over `0.5` (full moon) back to `1.0` (new moon).
+    pub fn from_cycle(cycle: f32) -> Self {
+        let cycle = cycle.rem_euclid(1.0);
+        if cycle <= 0.5 {
+            Self::new(2.0 * cycle)
+        } else {
+            Self::new(2.0 * (1.0 - cycle)).waning()
+        }
+    }
+
+    pub fn waxing(mut self) -> Self {
+        self.direction = MoonDirection::Waxing;
+        self
+    }
+
+    pub fn waning(mut self) -> Self {
+        self.direction = MoonDirection::Waning;
+        self
+    }
+
+    pub fn with_tilt(mut self, tilt: f32) -> Self {
+        self.tilt = tilt;
+        self
+    }
+
+    pub fn paint(
+        self,
+        painter: &Painter,
+        center: Pos2,
+        radius: f32,
+        color: Color32,
+        style: IconStyle,
+    ) {
+        moon::moon_phase(painter, center, radius, color, style, self);
+    }
+
+    /// Paints the moon as large as possible in the center of `rect`.
+    pub fn paint_in_rect(self, painter: &Painter, rect: Rect, color: Color32, style: IconStyle) {
+        let radius = 0.5 * rect.width().min(rect.height());
+        self.paint(painter, rect.center(), radius, color, style);
+    }
+}
@@ -17,19 +17,175 @@
 
 use egui::emath::{Pos2, Rect};
 use egui::epaint::Color32;
-use egui::{Painter, Response, ThemePreference, Ui, Widget};
+use egui::{
+    Context, Id, Label, Painter, Response, RichText, Sense, Theme, ThemePreference, Ui, Widget,
+};
+use icons::{Glyph, IconSet};
+use std::hash::Hash;
+#[cfg(not(target_arch = "wasm32"))]
+use system_theme::NextSwitch;
 
-mod arc;
+pub mod arc;
+mod automatic;
 mod cogwheel;
+mod contrast;
+#[cfg(feature = "headless")]
+pub mod headless;
+pub mod icons;
+mod monitor;
 mod moon;
+mod preview;
 mod rotated_rect;
 mod sun;
+pub mod system_theme;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod theme_change;
+mod theme_styles;
+mod themed_image;
+mod transition;
+
+pub use rotated_rect::RotatedRect;
+pub use theme_styles::ThemeStyles;
+pub use themed_image::ThemedImage;
+pub use transition::ThemeTransition;
 
 /// A switch control to configure the global theme preference.
+///
+/// See [`GlobalThemeSwitch`] for more options.
 pub fn global_theme_switch(ui: &mut Ui) {
-    let mut preference = ui.ctx().options(|opt| opt.theme_preference);
-    if ui.add(ThemeSwitch::new(&mut preference)).changed() {
-        ui.ctx().set_theme(preference);
+    ui.add(GlobalThemeSwitch::new());
+}
+
+/// A switch control to configure the global theme preference,
+/// with an optional transition between themes.
+///
+/// ```
+/// use egui_theme_switch::{GlobalThemeSwitch, ThemeTransition};
+///
+/// # egui::__run_test_ui(|ui| {
+/// ui.add(GlobalThemeSwitch::new().transition(ThemeTransition::cross_fade(0.3)));
+/// ui.add(GlobalThemeSwitch::new().transition(ThemeTransition::circular_reveal(0.5)));
+/// # });
+/// ```
+#[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
+#[derive(Debug, Default)]
+pub struct GlobalThemeSwitch {
+    transition: Option<ThemeTransition>,
+    icon_set: IconSet,
+    responsive: bool,
+    label: Option<RichText>,
+    label_position: LabelPosition,
+    accessible_name: Option<String>,
+    id_salt: Option<Id>,
+    styles: Option<ThemeStyles>,
+    preview_delay: Option<f32>,
+}
+
+impl GlobalThemeSwitch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Animates the change from one theme to the other.
+    pub fn transition(mut self, transition: ThemeTransition) -> Self {
+        self.transition = Some(transition);
+        self
+    }
+
+    pub fn icon_set(mut self, icon_set: IconSet) -> Self {
+        self.icon_set = icon_set;
+        self
+    }
+
+    /// See [`ThemeSwitch::responsive`].
+    pub fn responsive(mut self, responsive: bool) -> Self {
+        self.responsive = responsive;
+        self
+    }
+
+    /// See [`ThemeSwitch::label`].
+    pub fn label(mut self, label: impl Into<RichText>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// See [`ThemeSwitch::label_position`].
+    pub fn label_position(mut self, label_position: LabelPosition) -> Self {
+        self.label_position = label_position;
+        self
+    }
+
+    /// See [`ThemeSwitch::accessible_name`].
+    pub fn accessible_name(mut self, accessible_name: impl Into<String>) -> Self {
+        self.accessible_name = Some(accessible_name.into());
+        self
+    }
+
+    /// See [`ThemeSwitch::id_salt`].
+    pub fn id_salt(mut self, id_salt: impl Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    /// Installs these styles for the dark and light theme instead of egui's default ones.
+    pub fn styles(mut self, styles: ThemeStyles) -> Self {
+        self.styles = Some(styles);
+        self
+    }
+
+    /// Previews a theme when its button has been hovered for `delay` seconds,
+    /// moving the pointer away reverts it. Only a click changes the preference.
+    ///
+    /// The preview swaps the current theme's style, so [`Context::theme`] doesn't change.
+    /// Anything that follows the theme rather than the style doesn't show the preview:
+    /// [`ThemedImage`] keeps showing the current theme's variant and
+    /// [`theme_change`] observers aren't called.
+    pub fn preview_on_hover(mut self, delay: f32) -> Self {
+        self.preview_delay = Some(delay);
+        self
+    }
+}
+
+impl Widget for GlobalThemeSwitch {
+    fn ui(self, ui: &mut Ui) -> Response {
+        if let Some(styles) = &self.styles {
+            styles.install(ui.ctx());
+        }
+        let mut preference = ui.ctx().options(|opt| opt.theme_preference);
+        let switch = ThemeSwitch {
+            value: &mut preference,
+            icon_set: self.icon_set,
+            responsive: self.responsive,
+            label: self.label,
+            label_position: self.label_position,
+            accessible_name: self.accessible_name,
+            id_salt: self.id_salt,
+        };
+        let SwitchOutput {
+            response,
+            clicked,
+            hovered,
+            ..
+        } = switch.show(ui);
+        if let Some(clicked) = &clicked {
+            theme_change::record_cause(ui.ctx(), theme_change::Cause::User);
+            match self.transition {
+                Some(transition) => {
+                    transition::set_theme(ui.ctx(), preference, transition, clicked.center)
+                }
+                None => ui.ctx().set_theme(preference),
+            }
+        }
+        if let Some(delay) = self.preview_delay {
+            // The preview ends after the theme is changed, so that
+            // a transition starts from what the user was looking at.
+            match clicked {
+                Some(_) => preview::end(ui.ctx()),
+                None => preview::update(ui.ctx(), response.id, hovered, delay),
+            }
+        }
+        response
     }
 }
 
@@ -38,91 +194,270 @@ pub fn global_theme_switch(ui: &mut Ui) {
 ///
 /// ```
 /// use egui::ThemePreference;
-/// use egui_theme_switch::ThemeSwitch;
+/// use egui_theme_switch::icons::IconSet;
+/// use egui_theme_switch::{LabelPosition, ThemeSwitch};
 ///
 /// # egui::__run_test_ui(|ui| {
 /// let mut preference = ThemePreference::System;
 /// if ui.add(ThemeSwitch::new(&mut preference)).changed() {
 ///     // ...
 /// }
+///
+/// // A monitor instead of a cogwheel for following the system theme:
+/// ui.add(ThemeSwitch::new(&mut preference).icon_set(IconSet::Device));
+///
+/// // Collapses into a single button in narrow panels:
+/// ui.add(ThemeSwitch::new(&mut preference).responsive(true));
+///
+/// // With a caption above the switch:
+/// ui.add(
+///     ThemeSwitch::new(&mut preference)
+///         .label("Appearance")
+///         .label_position(LabelPosition::Above),
+/// );
 /// # });
 /// ```
 #[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
 #[derive(Debug)]
 pub struct ThemeSwitch<'a> {
     value: &'a mut ThemePreference,
+    icon_set: IconSet,
+    responsive: bool,
+    label: Option<RichText>,
+    label_position: LabelPosition,
+    accessible_name: Option<String>,
+    id_salt: Option<Id>,
+}
+
+/// Where the visible label is placed relative to the switch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LabelPosition {
+    /// Before the switch on the same line (after it in right-to-left layouts).
+    #[default]
+    Left,
+    /// On its own line above the switch.
+    Above,
+    /// After the switch on the same line (before it in right-to-left layouts).
+    Right,
 }
 
 impl<'a> ThemeSwitch<'a> {
     pub fn new(value: &'a mut ThemePreference) -> Self {
-        Self { value }
+        Self {
+            value,
+            icon_set: IconSet::default(),
+            responsive: false,
+            label: None,
+            label_position: LabelPosition::default(),
+            accessible_name: None,
+            id_salt: None,
+        }
     }
-}
 
-impl Widget for ThemeSwitch<'_> {
-    fn ui(self, ui: &mut crate::Ui) -> crate::Response {
-        static OPTIONS: [SwitchOption<ThemePreference>; 3] = [
+    pub fn icon_set(mut self, icon_set: IconSet) -> Self {
+        self.icon_set = icon_set;
+        self
+    }
+
+    /// When the switch doesn't fit into the available width, it collapses into
+    /// a single button showing the current value. Clicking it opens a popup with all options.
+    pub fn responsive(mut self, responsive: bool) -> Self {
+        self.responsive = responsive;
+        self
+    }
+
+    /// Shows a caption next to the switch. Screen readers announce it as the switch's name
+    /// and clicking it focuses the switch.
+    pub fn label(mut self, label: impl Into<RichText>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Where the [label](Self::label) is placed, to the left of the switch by default.
+    /// Has no effect without a label.
+    pub fn label_position(mut self, label_position: LabelPosition) -> Self {
+        self.label_position = label_position;
+        self
+    }
+
+    /// The name announced by screen readers.
+    /// Defaults to the visible label or "Theme" if there is none.
+    pub fn accessible_name(mut self, accessible_name: impl Into<String>) -> Self {
+        self.accessible_name = Some(accessible_name.into());
+        self
+    }
+
+    /// By default, the switch's id is generated from its position in the layout.
+    /// Use a salt that is unique within the parent ui to keep the id (and with it the
+    /// indicator animation) stable when the layout around the switch changes.
+    pub fn id_salt(mut self, id_salt: impl Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    fn show(mut self, ui: &mut Ui) -> SwitchOutput<ThemePreference> {
+        if self.accessible_name.is_none() {
+            self.accessible_name = self.label.as_ref().map(|label| label.text().to_owned());
+        }
+        // Resolved here so that wrapping the switch in a layout for the label doesn't change it.
+        let id = self.id_salt.map(|salt| ui.make_persistent_id(salt));
+        let Some(label) = self.label.take() else {
+            return self.show_switch(ui, id);
+        };
+
+        let caption = |ui: &mut Ui| ui.add(Label::new(label).selectable(false).sense(Sense::CLICK));
+        let (caption, mut output) = match self.label_position {
+            LabelPosition::Left => ui.horizontal(|ui| (caption(ui), self.show_switch(ui, id))),
+            LabelPosition::Above => ui.vertical(|ui| (caption(ui), self.show_switch(ui, id))),
+            LabelPosition::Right => ui.horizontal(|ui| {
+                let switch = self.show_switch(ui, id);
+                (caption(ui), switch)
+            }),
+        }
+        .inner;
+
+        if caption.clicked() {
+            ui.memory_mut(|memory| memory.request_focus(output.focus));
+        }
+        output.response = output.response.labelled_by(caption.id);
+        output
+    }
+
+    fn show_switch(self, ui: &mut Ui, id: Option<Id>) -> SwitchOutput<ThemePreference> {
+        let [system_icon, dark_icon, light_icon] = self.icon_set.glyphs();
+        let options = [
             SwitchOption {
                 value: ThemePreference::System,
-                icon: cogwheel::cogwheel,
+                icon: system_icon,
                 label: "Follow System",
+                hint: follow_system_hint(ui.ctx()),
             },
             SwitchOption {
                 value: ThemePreference::Dark,
-                icon: moon::moon,
+                icon: dark_icon,
                 label: "Dark",
+                hint: None,
             },
             SwitchOption {
                 value: ThemePreference::Light,
-                icon: sun::sun,
+                icon: light_icon,
                 label: "Light",
+                hint: None,
             },
         ];
-        let (update, response) = switch(ui, *self.value, "Theme", &OPTIONS);
+        let name = self.accessible_name.as_deref().unwrap_or("Theme");
+        let output = switch(ui, id, *self.value, name, &options, self.responsive);
 
-        if let Some(value) = update {
-            *self.value = value;
+        if let Some(clicked) = &output.clicked {
+            *self.value = clicked.value;
         }
 
-        response
+        output
     }
 }
 
+impl Widget for ThemeSwitch<'_> {
+    fn ui(self, ui: &mut crate::Ui) -> crate::Response {
+        self.show(ui).response
+    }
+}
+
+// Tells the user when a schedule will switch themes next.
+#[cfg(not(target_arch = "wasm32"))]
+fn follow_system_hint(ctx: &Context) -> Option<String> {
+    let next_switch = NextSwitch::load(ctx)?;
+    let theme = match next_switch.theme {
+        Theme::Dark => "dark",
+        Theme::Light => "light",
+    };
+    Some(format!("Switches to {theme} at {}", next_switch.local_time))
+}
+
+// Schedules aren't available on the web.
+#[cfg(target_arch = "wasm32")]
+fn follow_system_hint(_ctx: &Context) -> Option<String> {
+    None
+}
+
 #[derive(Debug, Clone)]
 struct SwitchOption<T> {
     value: T,
-    icon: IconPainter,
+    icon: Glyph,
     label: &'static str,
+    /// Additional information shown in the tooltip.
+    hint: Option<String>,
+}
+
+/// What happened to a switch in this pass.
+struct SwitchOutput<T> {
+    response: Response,
+    clicked: Option<Clicked<T>>,
+    /// The option whose button is hovered.
+    hovered: Option<T>,
+    /// The widget that is focused when the label is clicked.
+    focus: Id,
 }
 
-type IconPainter = fn(&Painter, Pos2, f32, Color32);
+/// A button that was clicked and changed the value.
+#[derive(Debug, Clone)]
+struct Clicked<T> {
+    value: T,
+    center: Pos2,
+}
 
+/// The id of the switch is generated from its position unless `id` is given.
 fn switch<T>(
     ui: &mut Ui,
+    id: Option<Id>,
     value: T,
     label: &str,
     options: &[SwitchOption<T>],
-) -> (Option<T>, Response)
+    responsive: bool,
+) -> SwitchOutput<T>
 where
-    T: PartialEq + Clone,
+    T: PartialEq + Clone + Send + Sync + 'static,
 {
-    let mut space = space_allocation::allocate_space(ui, options);
+    if responsive && !space_allocation::fits(ui, options) {
+        return compact::switch(ui, id, value, label, options);
+    }
 
-    let updated_value = interactivity::update_value_on_click(&mut space, &value);
-    let value = updated_value.clone().unwrap_or(value);
+    let mut space = space_allocation::allocate_space(ui, id, options, &value);
+
+    let clicked = interactivity::update_value_on_click(&mut space, &value);
+    let clicked = clicked.or_else(|| interactivity::update_value_on_arrow_key(ui, &mut space));
+    let value = clicked.as_ref().map_or(value, |c| c.value.clone());
+    // Every pass, so that keyboard use is noticed while the switch is scrolled out of view.
+    let focus_visible = interactivity::focus_visible(ui);
 
     if ui.is_rect_visible(space.rect) {
-        painting::draw_switch_background(ui, &space);
+        // The buttons draw their own focus ring.
+        let focused = space.buttons.iter().any(|b| b.response.has_focus());
+        painting::draw_switch_background(ui, space.rect, focused);
         painting::draw_active_indicator(ui, &space, &value);
 
         for button in &space.buttons {
-            painting::draw_button(ui, button, value == button.option.value);
+            let selected = value == button.option.value;
+            painting::draw_button(ui, button, selected, focus_visible);
         }
     }
 
     accessibility::attach_widget_info(ui, &space, label, &value);
 
-    (updated_value, unioned_response(space))
+    #[cfg(feature = "testing")]
+    testing::record(ui.ctx(), testing::SwitchLayout::from_space(&space));
+
+    let focus = interactivity::focus_target(&space, &value);
+    let hovered = space
+        .buttons
+        .iter()
+        .find(|button| button.response.hovered())
+        .map(|button| button.option.value.clone());
+    SwitchOutput {
+        response: unioned_response(space),
+        clicked,
+        hovered,
+        focus,
+    }
 }
 
 fn unioned_response<T>(space: AllocatedSpace<T>) -> Response {
@@ -137,6 +472,8 @@ struct AllocatedSpace<T> {
     rect: Rect,
     buttons: Vec<ButtonSpace<T>>,
     radius: f32,
+    /// The first option is on the right.
+    right_to_left: bool,
 }
 
 struct ButtonSpace<T> {
@@ -148,16 +485,25 @@ struct ButtonSpace<T> {
 
 mod space_allocation {
     use super::*;
-    use egui::emath::vec2;
+    use egui::emath::{vec2, Vec2};
     use egui::{Id, Sense};
 
-    pub(super) fn allocate_space<T>(ui: &mut Ui, options: &[SwitchOption<T>]) -> AllocatedSpace<T>
+    pub(super) fn allocate_space<T>(
+        ui: &mut Ui,
+        id: Option<Id>,
+        options: &[SwitchOption<T>],
+        value: &T,
+    ) -> AllocatedSpace<T>
     where
-        T: Clone,
+        T: PartialEq + Clone,
     {
-        let (rect, response, measurements) = allocate_switch(ui, options);
+        let (rect, response, measurements) = allocate_switch(ui, id, options);
         let id = response.id;
 
+        // The switch is not focusable, so it doesn't get an AccessKit node by itself.
+        #[cfg(feature = "accesskit")]
+        ui.ctx().accesskit_node_builder(id, |_| {});
+
         // Focusable elements always get an accessible node, so let's ensure that
         // the parent is set correctly when the responses are created the first time.
         ui.ctx().with_accessibility_parent(id, || {
@@ -165,7 +511,16 @@ mod space_allocation {
                 .iter()
                 .enumerate()
                 .scan(rect, |remaining, (n, option)| {
-                    Some(allocate_button(ui, remaining, id, &measurements, n, option))
+                    let selected = &option.value == value;
+                    Some(allocate_button(
+                        ui,
+                        remaining,
+                        id,
+                        &measurements,
+                        n,
+                        option,
+                        selected,
+                    ))
                 })
                 .collect();
 
@@ -174,27 +529,54 @@ mod space_allocation {
                 rect,
                 buttons,
                 radius: measurements.radius,
+                right_to_left: measurements.right_to_left,
             }
         })
     }
 
+    /// Whether the whole switch fits into the available width.
+    pub(super) fn fits<T>(ui: &Ui, options: &[SwitchOption<T>]) -> bool {
+        min_size(ui, options.len()).x <= ui.available_width()
+    }
+
+    /// The size of a switch with the given number of buttons when there's no extra space.
+    pub(super) fn min_size(ui: &Ui, buttons: usize) -> Vec2 {
+        let diameter = ui.spacing().interact_size.y;
+        let padding = ui.spacing().button_padding.min_elem();
+        let min_gap = 0.5 * ui.spacing().item_spacing.x;
+        let gap_count = buttons.saturating_sub(1) as f32;
+        vec2(
+            buttons as f32 * diameter + (gap_count * min_gap) + (2.0 * padding),
+            diameter + (2.0 * padding),
+        )
+    }
+
+    /// Like [`Ui::allocate_at_least`], but with the given id instead of the next automatic one.
+    pub(super) fn allocate_at_least(
+        ui: &mut Ui,
+        id: Option<Id>,
+        desired_size: Vec2,
+        sense: Sense,
+    ) -> (Rect, Response) {
+        let (auto_id, rect) = ui.allocate_space(desired_size);
+        let mut response = ui.interact(rect, id.unwrap_or(auto_id), sense);
+        response.intrinsic_size = Some(desired_size);
+        (rect, response)
+    }
+
     fn allocate_switch<T>(
         ui: &mut Ui,
+        id: Option<Id>,
         options: &[SwitchOption<T>],
     ) -> (Rect, Response, SwitchMeasurements) {
         let diameter = ui.spacing().interact_size.y;
         let radius = diameter / 2.0;
         let padding = ui.spacing().button_padding.min_elem();
-        let min_gap = 0.5 * ui.spacing().item_spacing.x;
         let gap_count = options.len().saturating_sub(1) as f32;
         let button_count = options.len() as f32;
 
-        let min_size = vec2(
-            button_count * diameter + (gap_count * min_gap) + (2.0 * padding),
-            diameter + (2.0 * padding),
-        );
-        let sense = Sense::focusable_noninteractive();
-        let (rect, response) = ui.allocate_at_least(min_size, sense);
+        let min_size = min_size(ui, options.len());
+        let (rect, response) = allocate_at_least(ui, id, min_size, switch_sense());
 
         // The space we're given might be larger so we calculate
         // the margin based on the allocated rect.
@@ -206,16 +588,32 @@ mod space_allocation {
             radius,
             padding,
             buttons: options.len(),
+            right_to_left: ui.layout().prefer_right_to_left(),
         };
 
         (rect, response, measurements)
     }
 
+    // The switch is a single radio group: only the selected button
+    // can be reached with tab and the arrow keys move the selection.
+    fn switch_sense() -> Sense {
+        Sense::hover()
+    }
+
+    fn button_sense(selected: bool) -> Sense {
+        if selected {
+            Sense::click()
+        } else {
+            Sense::CLICK
+        }
+    }
+
     struct SwitchMeasurements {
         gap: f32,
         radius: f32,
         padding: f32,
         buttons: usize,
+        right_to_left: bool,
     }
 
     fn allocate_button<T>(
@@ -225,12 +623,17 @@ mod space_allocation {
         measurements: &SwitchMeasurements,
         n: usize,
         option: &SwitchOption<T>,
+        selected: bool,
     ) -> ButtonSpace<T>
     where
         T: Clone,
     {
         let (rect, center) = partition(remaining, measurements, n);
-        let response = ui.interact(rect, switch_id.with(n), Sense::click());
+        let response = ui.interact(rect, switch_id.with(n), button_sense(selected));
+        // Buttons that are not focusable don't get a node right away,
+        // we create it here so that the nodes are in the same order as the buttons.
+        #[cfg(feature = "accesskit")]
+        ui.ctx().accesskit_node_builder(response.id, |_| {});
         ButtonSpace {
             center,
             response,
@@ -239,17 +642,26 @@ mod space_allocation {
         }
     }
 
+    // Buttons are split off the leading edge: the left one, or the right one in right-to-left layouts.
     fn partition(
         remaining: &mut Rect,
         measurements: &SwitchMeasurements,
         n: usize,
     ) -> (Rect, Pos2) {
         let (leading, trailing) = offset(n, measurements);
-        let center = remaining.left_center() + vec2(leading + measurements.radius, 0.0);
-        let right = remaining.min.x + leading + 2.0 * measurements.radius + trailing;
-        let (rect, new_remaining) = remaining.split_left_right_at_x(right);
-        *remaining = new_remaining;
-        (rect, center)
+        let width = leading + 2.0 * measurements.radius + trailing;
+        let to_center = vec2(leading + measurements.radius, 0.0);
+        if measurements.right_to_left {
+            let center = remaining.right_center() - to_center;
+            let (new_remaining, rect) = remaining.split_left_right_at_x(remaining.max.x - width);
+            *remaining = new_remaining;
+            (rect, center)
+        } else {
+            let center = remaining.left_center() + to_center;
+            let (rect, new_remaining) = remaining.split_left_right_at_x(remaining.min.x + width);
+            *remaining = new_remaining;
+            (rect, center)
+        }
     }
 
     // Calculates the leading and trailing space for a button.
@@ -270,10 +682,230 @@ mod space_allocation {
     }
 }
 
+/// The switch collapsed into a single button showing the current value,
+/// clicking it opens a popup listing all options.
+mod compact {
+    use super::*;
+    use egui::emath::{pos2, vec2};
+    use egui::{
+        Align, Layout, PopupCloseBehavior, Sense, StrokeKind, TextStyle, TextWrapMode, WidgetInfo,
+        WidgetText, WidgetType,
+    };
+
+    pub(super) fn switch<T>(
+        ui: &mut Ui,
+        id: Option<Id>,
+        value: T,
+        label: &str,
+        options: &[SwitchOption<T>],
+    ) -> SwitchOutput<T>
+    where
+        T: PartialEq + Clone + Send + Sync + 'static,
+    {
+        let size = space_allocation::min_size(ui, 1);
+        let (rect, mut response) =
+            space_allocation::allocate_at_least(ui, id, size, Sense::click());
+        let rect = ui.layout().align_size_within_rect(size, rect);
+        let popup_id = response.id.with("popup");
+        if response.clicked() {
+            ui.memory_mut(|memory| memory.toggle_popup(popup_id));
+        }
+
+        let current = options.iter().find(|option| option.value == value);
+        let focus_visible = interactivity::focus_visible(ui);
+        if let Some(option) = current {
+            if ui.is_rect_visible(rect) {
+                painting::draw_switch_background(ui, rect, response.has_focus());
+                draw_button(ui, rect, &response, option);
+                if focus_visible && response.has_focus() {
+                    painting::draw_switch_focus_ring(ui, rect);
+                }
+            }
+            attach_widget_info(ui, &response, label, option);
+        }
+
+        let (clicked, rows) = egui::popup_below_widget(
+            ui,
+            popup_id,
+            &response,
+            PopupCloseBehavior::CloseOnClickOutside,
+            |ui| option_list(ui, options, &value),
+        )
+        .unwrap_or_default();
+
+        #[cfg(feature = "testing")]
+        testing::record(
+            ui.ctx(),
+            testing::SwitchLayout::collapsed(&response, options, &rows),
+        );
+
+        let clicked = clicked.map(|value| {
+            ui.memory_mut(|memory| memory.close_popup());
+            response.mark_changed();
+            Clicked {
+                value,
+                center: rect.center(),
+            }
+        });
+        let hovered = options
+            .iter()
+            .zip(&rows)
+            .find(|(_, row)| row.hovered())
+            .map(|(option, _)| option.value.clone());
+        let focus = response.id;
+        SwitchOutput {
+            response,
+            clicked,
+            hovered,
+            focus,
+        }
+    }
+
+    // Looks like a switch with only the active button.
+    fn draw_button<T: Clone>(ui: &Ui, rect: Rect, response: &Response, option: &SwitchOption<T>) {
+        let radius = 0.5 * ui.spacing().interact_size.y;
+        let button = ButtonSpace {
+            center: rect.center(),
+            response: response.clone(),
+            radius,
+            option: option.clone(),
+        };
+        painting::draw_indicator(ui, button.center, radius);
+        // The focus ring goes around the whole switch instead.
+        painting::draw_button(ui, &button, true, false);
+    }
+
+    /// Returns the clicked value (if it changed) and the responses of all rows.
+    fn option_list<T>(
+        ui: &mut Ui,
+        options: &[SwitchOption<T>],
+        value: &T,
+    ) -> (Option<T>, Vec<Response>)
+    where
+        T: PartialEq + Clone,
+    {
+        let layout = Layout::top_down_justified(Align::LEFT);
+        ui.with_layout(layout, |ui| {
+            let rows: Vec<_> = options
+                .iter()
+                .map(|option| option_row(ui, option, &option.value == value))
+                .collect();
+            let clicked = options
+                .iter()
+                .zip(&rows)
+                .find(|(option, row)| row.clicked() && &option.value != value)
+                .map(|(option, _)| option.value.clone());
+            (clicked, rows)
+        })
+        .inner
+    }
+
+    fn option_row<T>(ui: &mut Ui, option: &SwitchOption<T>, selected: bool) -> Response {
+        let padding = ui.spacing().button_padding;
+        let icon_size = ui.spacing().interact_size.y;
+        let text = WidgetText::from(option.label).into_galley(
+            ui,
+            Some(TextWrapMode::Extend),
+            f32::INFINITY,
+            TextStyle::Button,
+        );
+        let size = vec2(
+            padding.x + icon_size + padding.x + text.size().x + padding.x,
+            icon_size.max(text.size().y + 2.0 * padding.y),
+        );
+        let (rect, response) = ui.allocate_at_least(size, Sense::click());
+        response.widget_info(|| {
+            WidgetInfo::selected(
+                WidgetType::SelectableLabel,
+                ui.is_enabled(),
+                selected,
+                option.label,
+            )
+        });
+
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.style().interact_selectable(&response, selected);
+            let painter = ui.painter();
+            if selected || response.hovered() || response.highlighted() || response.has_focus() {
+                painter.rect(
+                    rect.expand(visuals.expansion),
+                    visuals.corner_radius,
+                    visuals.weak_bg_fill,
+                    visuals.bg_stroke,
+                    StrokeKind::Inside,
+                );
+            }
+            let icon_center = pos2(rect.left() + padding.x + 0.5 * icon_size, rect.center().y);
+            (option.icon).paint(painter, icon_center, 0.25 * icon_size, visuals.text_color());
+            let text_pos = pos2(
+                icon_center.x + 0.5 * icon_size + padding.x,
+                rect.center().y - 0.5 * text.size().y,
+            );
+            painter.galley(text_pos, text, visuals.text_color());
+        }
+
+        match &option.hint {
+            Some(hint) => response.on_hover_text(hint),
+            None => response,
+        }
+    }
+
+    fn attach_widget_info<T>(ui: &Ui, response: &Response, label: &str, option: &SwitchOption<T>) {
+        let current = option.label;
+        response.widget_info(|| {
+            WidgetInfo::labeled(
+                WidgetType::ComboBox,
+                ui.is_enabled(),
+                format!("{label}: {current}"),
+            )
+        });
+        response.clone().on_hover_text(current);
+    }
+}
+
 mod interactivity {
     use super::*;
+    use egui::Event;
+
+    /// Whether focus should be shown, i.e. whether the keyboard was used more recently than
+    /// the pointer. Like `:focus-visible` in browsers, clicking a button doesn't show its focus.
+    pub(super) fn focus_visible(ui: &Ui) -> bool {
+        let (pointer, keyboard) = ui.input(|input| {
+            let keyboard = input
+                .events
+                .iter()
+                .any(|event| matches!(event, Event::Key { pressed: true, .. }));
+            (input.pointer.any_pressed(), keyboard)
+        });
+        ui.data_mut(|d| {
+            let visible = d.get_temp_mut_or_default::<bool>(focus_visible_id());
+            if pointer {
+                *visible = false;
+            } else if keyboard {
+                *visible = true;
+            }
+            *visible
+        })
+    }
+
+    fn focus_visible_id() -> Id {
+        Id::new("egui_theme_switch::focus_visible")
+    }
+
+    /// The widget that is focused when the switch's label is clicked.
+    pub(super) fn focus_target<T: PartialEq>(space: &AllocatedSpace<T>, value: &T) -> Id {
+        // Only the selected button can be focused.
+        let selected = space
+            .buttons
+            .iter()
+            .find(|button| &button.option.value == value);
+        selected.map_or(space.response.id, |button| button.response.id)
+    }
 
-    pub(super) fn update_value_on_click<T>(space: &mut AllocatedSpace<T>, value: &T) -> Option<T>
+    pub(super) fn update_value_on_click<T>(
+        space: &mut AllocatedSpace<T>,
+        value: &T,
+    ) -> Option<Clicked<T>>
     where
         T: PartialEq + Clone,
     {
@@ -283,29 +915,105 @@ mod interactivity {
             .find(|b| b.response.clicked())
             .filter(|b| &b.option.value != value)?;
         clicked.response.mark_changed();
-        Some(clicked.option.value.clone())
+        Some(Clicked {
+            value: clicked.option.value.clone(),
+            center: clicked.center,
+        })
+    }
+
+    /// Moves the selection (and focus) to the previous or next
+    /// button when an arrow key is pressed on the selected button.
+    pub(super) fn update_value_on_arrow_key<T>(
+        ui: &Ui,
+        space: &mut AllocatedSpace<T>,
+    ) -> Option<Clicked<T>>
+    where
+        T: Clone,
+    {
+        use egui::{EventFilter, Key};
+
+        let count = space.buttons.len();
+        let focused = space.buttons.iter().position(|b| b.response.has_focus())?;
+        let focused_id = space.buttons[focused].response.id;
+        // Arrow keys would otherwise move the focus to a neighbouring widget.
+        ui.memory_mut(|memory| {
+            let filter = EventFilter {
+                horizontal_arrows: true,
+                vertical_arrows: true,
+                ..Default::default()
+            };
+            memory.set_focus_lock_filter(focused_id, filter);
+        });
+
+        // The arrow keys follow the visual order, which is mirrored in right-to-left layouts.
+        let (left, right) = if space.right_to_left {
+            (1, count - 1)
+        } else {
+            (count - 1, 1)
+        };
+        let step = ui.input(|input| {
+            if input.key_pressed(Key::ArrowLeft) {
+                Some(left)
+            } else if input.key_pressed(Key::ArrowRight) {
+                Some(right)
+            } else if input.key_pressed(Key::ArrowUp) {
+                Some(count - 1)
+            } else if input.key_pressed(Key::ArrowDown) {
+                Some(1)
+            } else {
+                None
+            }
+        })?;
+        let next = &mut space.buttons[(focused + step) % count];
+        next.response.request_focus();
+        next.response.mark_changed();
+        Some(Clicked {
+            value: next.option.value.clone(),
+            center: next.center,
+        })
     }
 }
 
 mod painting {
     use super::*;
+    use crate::system_theme::{self, Contrast, DesktopAppearance};
     use egui::emath::pos2;
     use egui::epaint::Stroke;
     use egui::style::WidgetVisuals;
-    use egui::{Id, StrokeKind};
+    use egui::StrokeKind;
 
-    pub(super) fn draw_switch_background<T>(ui: &Ui, space: &AllocatedSpace<T>) {
-        let rect = space.rect;
+    /// `focused` is whether the switch or one of its buttons has focus.
+    pub(super) fn draw_switch_background(ui: &Ui, rect: Rect, focused: bool) {
         let rounding = 0.5 * rect.height();
-        let WidgetVisuals {
-            bg_fill, bg_stroke, ..
-        } = switch_visuals(ui, &space.response);
+        let visuals = switch_visuals(ui, focused);
+        let bg_stroke = outline(ui, &visuals);
+        ui.painter().rect(
+            rect,
+            rounding,
+            visuals.bg_fill,
+            bg_stroke,
+            StrokeKind::Middle,
+        );
+    }
+
+    /// A ring around the whole switch, for the compact switch which is focused as a whole.
+    pub(super) fn draw_switch_focus_ring(ui: &Ui, rect: Rect) {
+        let ring = ui.visuals().selection.stroke;
+        let bg_stroke = outline(ui, &switch_visuals(ui, true));
+        let rect = rect.expand(0.5 * bg_stroke.width + FOCUS_RING_GAP);
         ui.painter()
-            .rect(rect, rounding, bg_fill, bg_stroke, StrokeKind::Middle);
+            .rect_stroke(rect, 0.5 * rect.height(), ring, StrokeKind::Outside);
     }
 
-    fn switch_visuals(ui: &Ui, response: &Response) -> WidgetVisuals {
-        if response.has_focus() {
+    fn outline(ui: &Ui, visuals: &WidgetVisuals) -> Stroke {
+        contrast_stroke(ui, visuals.bg_stroke, visuals.fg_stroke.color)
+    }
+
+    /// The space between the switch's outline and its focus ring.
+    const FOCUS_RING_GAP: f32 = 2.0;
+
+    fn switch_visuals(ui: &Ui, focused: bool) -> WidgetVisuals {
+        if focused {
             ui.style().visuals.widgets.hovered
         } else {
             ui.style().visuals.widgets.inactive
@@ -317,37 +1025,105 @@ mod painting {
         space: &AllocatedSpace<T>,
         value: &T,
     ) {
-        let fill = ui.visuals().selection.bg_fill;
         if let Some(pos) = space
             .buttons
             .iter()
             .find(|button| &button.option.value == value)
             .map(|button| button.center)
         {
-            let pos = animate_active_indicator_position(ui, space.response.id, space.rect.min, pos);
-            ui.painter().circle(pos, space.radius, fill, Stroke::NONE);
+            let pos = animate_active_indicator_position(ui, space, pos);
+            draw_indicator(ui, pos, space.radius);
+        }
+    }
+
+    pub(super) fn draw_indicator(ui: &Ui, pos: Pos2, radius: f32) {
+        let fill = active_fill(ui);
+        let stroke = contrast_stroke(ui, Stroke::NONE, ui.visuals().selection.stroke.color);
+        ui.painter().circle(pos, radius, fill, stroke);
+    }
+
+    // The active indicator follows the desktop's accent colour if there is one.
+    fn active_fill(ui: &Ui) -> Color32 {
+        DesktopAppearance::load(ui.ctx())
+            .accent_color
+            .unwrap_or(ui.visuals().selection.bg_fill)
+    }
+
+    fn active_icon_color(ui: &Ui, visuals: &WidgetVisuals) -> Color32 {
+        DesktopAppearance::load(ui.ctx())
+            .accent_color
+            .map(system_theme::contrasting_text_color)
+            .unwrap_or(visuals.fg_stroke.color)
+    }
+
+    // When the desktop asks for high contrast we use
+    // strokes that are at least as strong as the foreground.
+    fn contrast_stroke(ui: &Ui, stroke: Stroke, color: Color32) -> Stroke {
+        if DesktopAppearance::load(ui.ctx()).contrast == Contrast::High {
+            Stroke::new(stroke.width.max(HIGH_CONTRAST_STROKE_WIDTH), color)
+        } else {
+            stroke
         }
     }
 
-    fn animate_active_indicator_position(ui: &Ui, id: Id, anchor: Pos2, pos: Pos2) -> Pos2 {
+    const HIGH_CONTRAST_STROKE_WIDTH: f32 = 2.0;
+
+    fn animate_active_indicator_position<T>(ui: &Ui, space: &AllocatedSpace<T>, pos: Pos2) -> Pos2 {
         let animation_time = ui.style().animation_time;
-        // Animate the relative position to prevent
-        // animating the active indicator when the switch itself is moved around.
-        let x = pos.x - anchor.x;
-        let x = anchor.x + ui.ctx().animate_value_with_time(id, x, animation_time);
-        pos2(x, pos.y)
+        // Animate the distance from the leading edge to prevent animating the active
+        // indicator when the switch itself is moved around or mirrored.
+        let (anchor, direction) = if space.right_to_left {
+            (space.rect.right(), -1.0)
+        } else {
+            (space.rect.left(), 1.0)
+        };
+        let distance = direction * (pos.x - anchor);
+        let distance =
+            ui.ctx()
+                .animate_value_with_time(space.response.id, distance, animation_time);
+        pos2(anchor + direction * distance, pos.y)
     }
 
-    pub(super) fn draw_button<T>(ui: &Ui, button: &ButtonSpace<T>, selected: bool) {
+    pub(super) fn draw_button<T>(
+        ui: &Ui,
+        button: &ButtonSpace<T>,
+        selected: bool,
+        focus_visible: bool,
+    ) {
         let visuals = ui.style().interact_selectable(&button.response, selected);
         let animation_factor = animate_click(ui, &button.response);
         let radius = animation_factor * button.radius;
         let icon_radius = 0.5 * radius * animation_factor;
         let bg_fill = button_fill(&button.response, &visuals);
+        let bg_stroke = if interacted(&button.response) {
+            contrast_stroke(ui, visuals.bg_stroke, visuals.fg_stroke.color)
+        } else {
+            visuals.bg_stroke
+        };
+        let icon_color = if selected {
+            active_icon_color(ui, &visuals)
+        } else {
+            visuals.fg_stroke.color
+        };
 
         let painter = ui.painter();
-        painter.circle(button.center, radius, bg_fill, visuals.bg_stroke);
-        (button.option.icon)(painter, button.center, icon_radius, visuals.fg_stroke.color);
+        painter.circle(button.center, radius, bg_fill, bg_stroke);
+        (button.option.icon).paint(painter, button.center, icon_radius, icon_color);
+
+        if focus_visible && button.response.has_focus() {
+            draw_button_focus_ring(ui, button);
+        }
+    }
+
+    // The ring goes into the padding between the button and the switch's outline
+    // (which isn't focused itself when one of its buttons is).
+    fn draw_button_focus_ring<T>(ui: &Ui, button: &ButtonSpace<T>) {
+        let ring = ui.visuals().selection.stroke;
+        let outline = outline(ui, &ui.visuals().widgets.inactive);
+        let gap = (ui.spacing().button_padding.min_elem() - 0.5 * outline.width).max(0.0);
+        let ring = Stroke::new(ring.width.min(gap), ring.color);
+        ui.painter()
+            .circle_stroke(button.center, button.radius + 0.5 * gap, ring);
     }
 
     // We want to avoid drawing a background when the button is either active itself or was previously active.
@@ -389,9 +1165,27 @@ mod accessibility {
             .response
             .widget_info(|| radio_group_widget_info(ui, label));
 
-        for button in &space.buttons {
-            let selected = value == &button.option.value;
-            attach_widget_info_to_button(ui, button, selected);
+        ui.ctx().with_accessibility_parent(space.response.id, || {
+            for button in &space.buttons {
+                let selected = value == &button.option.value;
+                attach_widget_info_to_button(ui, button, selected);
+            }
+        });
+
+        #[cfg(feature = "accesskit")]
+        set_positions_in_set(ui, space, value);
+    }
+
+    /// Lets screen readers announce e.g. "Dark, 2 of 3".
+    #[cfg(feature = "accesskit")]
+    fn set_positions_in_set<T: PartialEq>(ui: &Ui, space: &AllocatedSpace<T>, value: &T) {
+        let count = space.buttons.len();
+        for (n, button) in space.buttons.iter().enumerate() {
+            ui.ctx().accesskit_node_builder(button.response.id, |node| {
+                node.set_position_in_set(n + 1);
+                node.set_size_of_set(count);
+                node.set_selected(value == &button.option.value);
+            });
         }
     }
 
@@ -399,7 +1193,10 @@ mod accessibility {
         let response = &button.response;
         let label = button.option.label;
         response.widget_info(|| button_widget_info(ui, label, selected));
-        response.clone().on_hover_text(label);
+        match &button.option.hint {
+            Some(hint) => response.clone().on_hover_text(format!("{label}\n{hint}")),
+            None => response.clone().on_hover_text(label),
+        };
     }
 
     fn radio_group_widget_info(ui: &Ui, label: &str) -> WidgetInfo {
@@ -0,0 +1,41 @@
+use super::icons::IconStyle;
+use egui::emath::{pos2, vec2, Pos2, Rect};
+use egui::epaint::{Color32, Stroke, StrokeKind};
+use egui::Painter;
+
+pub(crate) fn monitor(
+    painter: &Painter,
+    center: Pos2,
+    radius: f32,
+    color: Color32,
+    style: IconStyle,
+    line_width: f32,
+) {
+    let screen = Rect::from_center_size(center - vec2(0., 0.2 * radius), vec2(1.8, 1.25) * radius);
+    let rounding = radius / 6.;
+
+    match style {
+        IconStyle::Filled => {
+            painter.rect_filled(screen, rounding, color);
+        }
+        IconStyle::Outline => {
+            painter.rect_stroke(screen, rounding, (line_width, color), StrokeKind::Inside);
+        }
+    }
+
+    // The stand is made of lines in both styles.
+    let stroke = Stroke::new(line_width, color);
+    let base_y = center.y + 0.8 * radius - line_width / 2.;
+    painter.line_segment(
+        [pos2(center.x, screen.bottom()), pos2(center.x, base_y)],
+        stroke,
+    );
+    let base_width = 0.45 * radius;
+    painter.line_segment(
+        [
+            pos2(center.x - base_width, base_y),
+            pos2(center.x + base_width, base_y),
+        ],
+        stroke,
+    );
+}
@@ -1,67 +1,142 @@
-use super::arc;
-use egui::epaint::{CubicBezierShape, PathShape, PathStroke};
-use egui::{Color32, Painter, Pos2, Vec2};
-use std::f32::consts::{PI, TAU};
+use super::arc::{self, EllipticalArc};
+use super::icons::{IconStyle, MoonDirection, MoonPhase};
+use egui::emath::{pos2, Rot2};
+use egui::epaint::{Mesh, PathShape, Stroke};
+use egui::{Color32, Painter, Pos2};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
 
-/// Draws an outlined moon symbol in the waxing crescent phase.
-pub(crate) fn moon(painter: &Painter, center: Pos2, radius: f32, color: Color32) {
-    let stroke_width = radius / 5.0;
+/// The phase and tilt of the moon symbol: a waxing crescent, lit from the lower left.
+const SYMBOL: MoonPhase = MoonPhase {
+    phase: 0.4,
+    direction: MoonDirection::Waxing,
+    tilt: 0.365 * TAU,
+};
 
-    let start = 0.04 * TAU;
-    let start_vec = radius * Vec2::angled(start);
-    let size = 0.65 * TAU;
-    let end_vec = radius * Vec2::angled(start + size);
+/// Draws a moon symbol in the waxing crescent phase.
+pub(crate) fn moon(
+    painter: &Painter,
+    center: Pos2,
+    radius: f32,
+    color: Color32,
+    style: IconStyle,
+    line_width: f32,
+) {
+    lit_part(painter, center, radius, color, style, SYMBOL, line_width);
+}
 
-    let direction_angle = start - (TAU - size) / 2.;
-    let direction = Vec2::angled(direction_angle);
+/// Draws the lit part of the moon in the given phase.
+pub(crate) fn moon_phase(
+    painter: &Painter,
+    center: Pos2,
+    radius: f32,
+    color: Color32,
+    style: IconStyle,
+    phase: MoonPhase,
+) {
+    let outline_width = radius / 8.0;
+    // Nothing is lit at new moon, so we only draw the outline to keep the moon visible.
+    if phase.phase <= 0.0 {
+        let stroke = Stroke::new(outline_width, color);
+        painter.circle_stroke(center, radius - outline_width / 2.0, stroke);
+        return;
+    }
+    lit_part(painter, center, radius, color, style, phase, outline_width);
+}
 
-    // We want to draw a circle with the same radius somewhere on the line
-    // `direction` such that it intersects with our first circle at `start` and `end`.
-    // The connection between the start and end points is a chord of our occluding circle.
-    let chord = start_vec - end_vec;
-    let angle = 2.0 * (chord.length() / (2.0 * radius)).asin();
-    let sagitta = radius * (1.0 - (angle / 2.0).cos());
-    let apothem = radius - sagitta;
-    let occluding_center = center + midpoint(start_vec, end_vec) + apothem * direction;
+fn lit_part(
+    painter: &Painter,
+    center: Pos2,
+    radius: f32,
+    color: Color32,
+    style: IconStyle,
+    phase: MoonPhase,
+    line_width: f32,
+) {
+    let tolerance = 0.1 / painter.pixels_per_point();
+    let radius = match style {
+        IconStyle::Filled => radius,
+        // The stroke is centered on the outline, this keeps it inside of `radius`.
+        IconStyle::Outline => radius - line_width / 2.0,
+    };
+    let lit = LitPart::new(radius, phase.phase, tolerance);
+    // Waning moons are lit from the other side, which is the same as turning them upside down.
+    let rot = Rot2::from_angle(match phase.direction {
+        MoonDirection::Waxing => phase.tilt,
+        MoonDirection::Waning => phase.tilt + PI,
+    });
+    let place = |point: Pos2| center + rot * point.to_vec2();
+    let points = lit.points().into_iter().map(place).collect();
 
-    let occlusion_start = direction_angle + PI - angle / 2.;
-    let occlusion_end = direction_angle + PI + angle / 2.;
+    match style {
+        IconStyle::Filled => {
+            // The lit part is not convex, so we can't use a [`PathShape`] to fill it.
+            let mut mesh = lit.fill(color);
+            mesh.rotate(rot, Pos2::ZERO);
+            mesh.translate(center.to_vec2());
+            painter.add(mesh);
+            // The mesh isn't anti-aliased, a hairline around it smoothens the edges.
+            let hairline = Stroke::new(1.0 / painter.pixels_per_point(), color);
+            painter.add(PathShape::closed_line(points, hairline));
+        }
+        IconStyle::Outline => {
+            painter.add(PathShape::closed_line(
+                points,
+                Stroke::new(line_width, color),
+            ));
+        }
+    }
+}
 
-    let main_arc = arc::approximate_with_beziers(
-        center,
-        radius,
-        start..=(start + size),
-        Color32::TRANSPARENT,
-        (stroke_width, color),
-    );
-    let occluding_arc = arc::approximate_with_beziers(
-        occluding_center,
-        radius,
-        occlusion_end..=occlusion_start,
-        Color32::TRANSPARENT,
-        (stroke_width, color),
-    );
+/// The lit part of a moon centered at the origin and lit from the right.
+///
+/// It's bounded by the right half of the moon's limb and the terminator, which we
+/// approximate with an arc of a second circle through the poles. That circle occludes
+/// the lit part up to half moon and bounds it beyond that.
+struct LitPart {
+    /// From the north pole to the south pole.
+    limb: EllipticalArc,
+    /// Also from the north pole to the south pole.
+    terminator: EllipticalArc,
+}
 
-    // We join the beziers together to a path which improves
-    // the drawing of the joints somewhat.
-    let path = to_path(main_arc.chain(occluding_arc), (stroke_width, color));
+impl LitPart {
+    fn new(radius: f32, phase: f32, tolerance: f32) -> Self {
+        // Where the terminator crosses the equator, from `radius` at new moon to `-radius` at full moon.
+        let bulge = radius * (PI * phase.clamp(0.0, 1.0)).cos();
+        // At half moon the terminator is a straight line, which a circle can only approximate.
+        let min_bulge = 1e-3 * radius;
+        let bulge = if bulge.abs() < min_bulge {
+            min_bulge.copysign(bulge)
+        } else {
+            bulge
+        };
 
-    painter.add(path);
-}
+        // The terminator's circle goes through both poles and through `(bulge, 0)`.
+        let center_x = (bulge * bulge - radius * radius) / (2.0 * bulge);
+        let terminator_radius = (bulge - center_x).abs();
+        let south = (radius).atan2(-center_x);
+        let north = if bulge > 0.0 { -south } else { TAU - south };
 
-fn midpoint(a: Vec2, b: Vec2) -> Vec2 {
-    0.5 * (a + b)
-}
+        Self {
+            limb: EllipticalArc::circle(Pos2::ZERO, radius, -FRAC_PI_2..=FRAC_PI_2)
+                .with_max_error(tolerance),
+            terminator: EllipticalArc::circle(
+                pos2(center_x, 0.0),
+                terminator_radius,
+                north..=south,
+            )
+            .with_max_error(tolerance),
+        }
+    }
+
+    /// The outline, along the limb and back along the terminator.
+    fn points(&self) -> Vec<Pos2> {
+        let mut points = self.limb.points();
+        points.extend(self.terminator.points().into_iter().rev());
+        points
+    }
 
-fn to_path(
-    beziers: impl IntoIterator<Item = CubicBezierShape>,
-    stroke: impl Into<PathStroke>,
-) -> PathShape {
-    let points = beziers.into_iter().flat_map(|b| b.flatten(None)).collect();
-    PathShape {
-        points,
-        closed: false,
-        fill: Default::default(),
-        stroke: stroke.into(),
+    fn fill(&self, color: Color32) -> Mesh {
+        arc::band_mesh(&self.limb, &self.terminator, color)
     }
 }
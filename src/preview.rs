@@ -0,0 +1,168 @@
+//! Shows a theme while its button is hovered without changing the preference.
+//!
+//! The preview replaces the current theme's style with the previewed theme's style.
+//! egui doesn't persist styles, so the preview can't end up in the saved preference
+//! even if the app is saved while previewing.
+//!
+//! Several switches can be shown at once (e.g. in a menu bar and in the settings),
+//! so the hovered option is remembered together with its switch and only that
+//! switch can end the preview by no longer being hovered.
+//! A switch that isn't shown can't report that it's no longer hovered
+//! (e.g. because its menu was closed), so the preview also ends
+//! at the end of every pass in which the hovered switch didn't report.
+
+use crate::transition;
+use egui::{Context, Id, Style, Theme, ThemePreference};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Default)]
+struct Preview {
+    hovered: Option<Hovered>,
+    active: Option<ActivePreview>,
+    registered: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Hovered {
+    switch: Id,
+    preference: ThemePreference,
+    since: f64,
+    /// The pass in which the switch last reported.
+    reported_in_pass: u64,
+}
+
+#[derive(Debug, Clone)]
+struct ActivePreview {
+    preference: ThemePreference,
+    /// The theme whose style is replaced.
+    theme: Theme,
+    original: Arc<Style>,
+}
+
+/// Starts previewing `hovered` after it has been hovered for `delay` seconds
+/// and ends the preview when nothing is hovered in the `switch` that started it.
+pub(crate) fn update(ctx: &Context, switch: Id, hovered: Option<ThemePreference>, delay: f32) {
+    let now = ctx.input(|input| input.time);
+    let mut preview = load_registered(ctx);
+    match hovered {
+        Some(preference) => {
+            let since = match &preview.hovered {
+                Some(previous)
+                    if previous.switch == switch && previous.preference == preference =>
+                {
+                    previous.since
+                }
+                _ => now,
+            };
+            preview.hovered = Some(Hovered {
+                switch,
+                preference,
+                since,
+                reported_in_pass: ctx.cumulative_pass_nr(),
+            });
+        }
+        // Another switch is hovered.
+        None if preview.hovered.as_ref().is_some_and(|h| h.switch != switch) => {}
+        None => preview.hovered = None,
+    }
+
+    match &preview.hovered {
+        Some(hovered) if hovered.switch == switch => {
+            let remaining = f64::from(delay) - (now - hovered.since);
+            if remaining <= 0.0 {
+                let preference = hovered.preference;
+                show(ctx, &mut preview, preference);
+            } else {
+                ctx.request_repaint_after_secs(remaining as f32);
+            }
+        }
+        Some(_) => {}
+        None => restore(ctx, &mut preview),
+    }
+    store(ctx, preview);
+}
+
+/// Ends the preview, e.g. because a button was clicked.
+pub(crate) fn end(ctx: &Context) {
+    let mut preview = load_registered(ctx);
+    preview.hovered = None;
+    restore(ctx, &mut preview);
+    store(ctx, preview);
+}
+
+fn end_pass(ctx: &Context) {
+    let mut preview = load(ctx);
+    let pass = ctx.cumulative_pass_nr();
+    if (preview.hovered.as_ref()).is_some_and(|hovered| hovered.reported_in_pass != pass) {
+        preview.hovered = None;
+        restore(ctx, &mut preview);
+        store(ctx, preview);
+    }
+}
+
+/// Loads the preview and makes sure that it's checked at the end of every pass.
+fn load_registered(ctx: &Context) -> Preview {
+    let mut preview = load(ctx);
+    if !preview.registered {
+        ctx.on_end_pass("theme_preview", Arc::new(end_pass));
+        preview.registered = true;
+    }
+    preview
+}
+
+fn show(ctx: &Context, preview: &mut Preview, preference: ThemePreference) {
+    let current = ctx.theme();
+    if preview
+        .active
+        .as_ref()
+        .is_some_and(|active| active.preference == preference && active.theme == current)
+    {
+        return;
+    }
+    restore(ctx, preview);
+    // A running cross-fade would otherwise keep overwriting the preview
+    // and we'd save one of its intermediate steps as the original.
+    transition::finish_cross_fade(ctx);
+
+    let theme = resolve(ctx, preference);
+    if theme != current {
+        let original = ctx.style_of(current);
+        ctx.set_style_of(current, ctx.style_of(theme));
+        ctx.request_repaint();
+        preview.active = Some(ActivePreview {
+            preference,
+            theme: current,
+            original,
+        });
+    }
+}
+
+fn restore(ctx: &Context, preview: &mut Preview) {
+    if let Some(active) = preview.active.take() {
+        ctx.set_style_of(active.theme, active.original);
+        ctx.request_repaint();
+    }
+}
+
+/// The theme egui would use if `preference` was selected.
+fn resolve(ctx: &Context, preference: ThemePreference) -> Theme {
+    match preference {
+        ThemePreference::Dark => Theme::Dark,
+        ThemePreference::Light => Theme::Light,
+        ThemePreference::System => ctx
+            .system_theme()
+            .unwrap_or_else(|| ctx.options(|opt| opt.fallback_theme)),
+    }
+}
+
+fn load(ctx: &Context) -> Preview {
+    ctx.data(|d| d.get_temp(preview_id())).unwrap_or_default()
+}
+
+fn store(ctx: &Context, preview: Preview) {
+    ctx.data_mut(|d| d.insert_temp(preview_id(), preview));
+}
+
+fn preview_id() -> Id {
+    Id::new("egui_theme_switch::Preview")
+}
@@ -1,68 +1,104 @@
-use egui::emath::{vec2, Pos2, Rect, Rot2};
-use egui::epaint::{Color32, CornerRadius, PathShape, Stroke};
+use super::arc::EllipticalArc;
+use egui::emath::{Pos2, Rect, Rot2, Vec2};
+use egui::epaint::{Color32, CornerRadiusF32, PathShape, Stroke};
 use egui::Painter;
+use std::f32::consts::{FRAC_PI_2, PI};
 
-/// Draws a rectangle with rounded corners, rotated around an origin.
-pub(crate) fn draw_rotated_rect(
-    painter: &Painter,
-    rect: Rect,
-    rounding: impl Into<CornerRadius>,
-    fill: impl Into<Color32>,
-    rot: impl Into<Rot2>,
-    origin: Pos2,
-) {
-    let rounding = rounding.into();
-    let fill = fill.into();
-    let rot = rot.into();
-    let safe_inset_points = safe_inset_points(rect, rot, origin, rounding);
+/// How far the flattened corners may deviate from a true arc, in physical pixels.
+const TOLERANCE_IN_PIXELS: f32 = 0.1;
 
-    painter.add(PathShape::convex_polygon(
-        safe_inset_points.to_vec(),
-        fill,
-        Stroke::NONE,
-    ));
-
-    // Nobody will notice that we're not actually drawing
-    // the round bits... 🤫
+/// A rectangle with rounded corners, rotated around an origin.
+///
+/// ```
+/// use egui::emath::{pos2, vec2, Rect, Rot2};
+/// use egui::epaint::{Color32, Stroke};
+/// use egui_theme_switch::RotatedRect;
+///
+/// # egui::__run_test_ui(|ui| {
+/// let rect = Rect::from_center_size(pos2(50.0, 20.0), vec2(10.0, 30.0));
+/// RotatedRect::new(rect, 5.0, Rot2::from_angle(0.25), pos2(50.0, 50.0)).paint(
+///     ui.painter(),
+///     Color32::GOLD,
+///     Stroke::NONE,
+/// );
+/// # });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotatedRect {
+    pub rect: Rect,
+    /// Corners can't be rounder than half of the shorter side, larger radii are clamped.
+    pub corner_radius: CornerRadiusF32,
+    pub rot: Rot2,
+    /// The point that the rectangle is rotated around.
+    pub origin: Pos2,
 }
 
-fn safe_inset_points(rect: Rect, rot: Rot2, origin: Pos2, rounding: CornerRadius) -> [Pos2; 8] {
-    [
-        rotate(rect.left_top() + vec2(0.0, rounding.nw as f32), rot, origin),
-        rotate(rect.left_top() + vec2(rounding.nw as f32, 0.0), rot, origin),
-        rotate(
-            rect.right_top() - vec2(rounding.ne as f32, 0.0),
-            rot,
-            origin,
-        ),
-        rotate(
-            rect.right_top() + vec2(0.0, rounding.ne as f32),
-            rot,
-            origin,
-        ),
-        rotate(
-            rect.right_bottom() - vec2(0.0, rounding.se as f32),
-            rot,
-            origin,
-        ),
-        rotate(
-            rect.right_bottom() - vec2(rounding.se as f32, 0.0),
-            rot,
-            origin,
-        ),
-        rotate(
-            rect.left_bottom() + vec2(rounding.sw as f32, 0.0),
-            rot,
-            origin,
-        ),
-        rotate(
-            rect.left_bottom() - vec2(0.0, rounding.sw as f32),
-            rot,
+impl RotatedRect {
+    pub fn new(
+        rect: Rect,
+        corner_radius: impl Into<CornerRadiusF32>,
+        rot: impl Into<Rot2>,
+        origin: Pos2,
+    ) -> Self {
+        Self {
+            rect,
+            corner_radius: corner_radius.into(),
+            rot: rot.into(),
             origin,
-        ),
-    ]
+        }
+    }
+
+    /// The outline in clockwise order. The corners are flattened finely enough
+    /// to look round at the given `pixels_per_point`, so large corners get more points.
+    pub fn points(&self, pixels_per_point: f32) -> Vec<Pos2> {
+        let tolerance = TOLERANCE_IN_PIXELS / pixels_per_point;
+        let rect = self.rect;
+        let corner_radius = self
+            .corner_radius
+            .at_most(0.5 * rect.width().min(rect.height()));
+        let corners = [
+            (rect.left_top(), corner_radius.nw, PI),
+            (rect.right_top(), corner_radius.ne, -FRAC_PI_2),
+            (rect.right_bottom(), corner_radius.se, 0.0),
+            (rect.left_bottom(), corner_radius.sw, FRAC_PI_2),
+        ];
+
+        let mut points = Vec::new();
+        for (corner, radius, start) in corners {
+            if radius <= 0.0 {
+                points.push(corner);
+            } else {
+                let end = start + FRAC_PI_2;
+                // The arc touches both sides of the corner.
+                let center = corner - radius * (Vec2::angled(start) + Vec2::angled(end));
+                points.extend(corner_points(center, radius, start, end, tolerance));
+            }
+        }
+        // Neighbouring corners meet when the radius is half of a side.
+        points.dedup_by(|a, b| a.distance(*b) < tolerance);
+        points.into_iter().map(|p| self.rotate(p)).collect()
+    }
+
+    pub fn to_shape(
+        &self,
+        pixels_per_point: f32,
+        fill: impl Into<Color32>,
+        stroke: impl Into<Stroke>,
+    ) -> PathShape {
+        PathShape::convex_polygon(self.points(pixels_per_point), fill, stroke.into())
+    }
+
+    pub fn paint(&self, painter: &Painter, fill: impl Into<Color32>, stroke: impl Into<Stroke>) {
+        painter.add(self.to_shape(painter.pixels_per_point(), fill, stroke));
+    }
+
+    fn rotate(&self, point: Pos2) -> Pos2 {
+        self.origin + self.rot * (point - self.origin)
+    }
 }
 
-fn rotate(point: Pos2, rot: Rot2, origin: Pos2) -> Pos2 {
-    origin + rot * (point - origin)
+fn corner_points(center: Pos2, radius: f32, start: f32, end: f32, tolerance: f32) -> Vec<Pos2> {
+    EllipticalArc::circle(center, radius, start..=end)
+        .with_max_error(tolerance)
+        .points()
 }
@@ -1,17 +1,35 @@
-use super::rotated_rect::draw_rotated_rect;
+use super::icons::IconStyle;
+use super::rotated_rect::RotatedRect;
 use egui::emath::{vec2, Pos2, Rect, Rot2, Vec2};
 use egui::epaint::{Color32, Stroke};
 use egui::Painter;
 use std::f32::consts::TAU;
 
-pub(crate) fn sun(painter: &Painter, center: Pos2, radius: f32, color: Color32) {
+pub(crate) fn sun(
+    painter: &Painter,
+    center: Pos2,
+    radius: f32,
+    color: Color32,
+    style: IconStyle,
+    line_width: f32,
+) {
     let clipped = painter.with_clip_rect(Rect::from_center_size(center, Vec2::splat(radius * 2.)));
     let sun_radius = radius * 0.5;
 
-    clipped.circle(center, sun_radius, color, Stroke::NONE);
+    // The outlined sun has thin rays so that all lines have the same weight.
+    let ray_radius = match style {
+        IconStyle::Filled => {
+            clipped.circle(center, sun_radius, color, Stroke::NONE);
+            radius / 4.
+        }
+        IconStyle::Outline => {
+            let stroke = Stroke::new(line_width, color);
+            clipped.circle_stroke(center, sun_radius - line_width / 2., stroke);
+            line_width
+        }
+    };
 
     let rays = 8;
-    let ray_radius = radius / 4.;
     let ray_spacing = radius / 7.5;
     let ray_length = radius - sun_radius - ray_spacing;
 
@@ -20,6 +38,10 @@ pub(crate) fn sun(painter: &Painter, center: Pos2, radius: f32, color: Color32)
         let ray_size = vec2(ray_radius, ray_length);
         let rect = Rect::from_center_size(ray_center, ray_size);
         let rotation = Rot2::from_angle(TAU / rays as f32 * n as f32);
-        draw_rotated_rect(painter, rect, ray_radius / 2.0, color, rotation, center);
+        RotatedRect::new(rect, ray_radius / 2.0, rotation, center).paint(
+            painter,
+            color,
+            Stroke::NONE,
+        );
     }
 }
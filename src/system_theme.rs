@@ -0,0 +1,393 @@
+//! Pluggable sources for the system theme.
+//!
+//! egui integrations don't detect the system theme on every platform
+//! (eframe doesn't follow the system theme on Linux, for example).
+//! A [`SystemThemeSource`] fills that gap: [`feed`] it into the raw input
+//! before the input is passed to egui (e.g. from `eframe::App::raw_input_hook`)
+//! and [`register`] it for the desktop appearance and change notifications.
+//!
+//! ```
+//! use egui::{RawInput, Theme};
+//! use egui_theme_switch::system_theme::{self, FakeSource};
+//!
+//! let ctx = egui::Context::default();
+//! let source = FakeSource::new(Some(Theme::Dark));
+//! system_theme::register(&ctx, source.clone());
+//!
+//! let run = |ctx: &egui::Context| {
+//!     let mut raw_input = RawInput::default();
+//!     system_theme::feed(&mut raw_input, &source);
+//!     let _ = ctx.run(raw_input, |_| {});
+//! };
+//!
+//! run(&ctx);
+//! assert_eq!(ctx.theme(), Theme::Dark);
+//!
+//! source.set(Some(Theme::Light));
+//! run(&ctx);
+//! assert_eq!(ctx.theme(), Theme::Light);
+//! ```
+
+use egui::style::Selection;
+use egui::{Color32, Context, Id, RawInput, Stroke, Theme};
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod config_file;
+#[cfg(not(target_arch = "wasm32"))]
+mod desktop_files;
+mod env;
+#[cfg(not(target_arch = "wasm32"))]
+mod gsettings;
+#[cfg(not(target_arch = "wasm32"))]
+mod polling;
+#[cfg(not(target_arch = "wasm32"))]
+mod schedule;
+#[cfg(all(feature = "xdg-portal", target_os = "linux"))]
+mod xdg_portal;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use config_file::ConfigFile;
+#[cfg(not(target_arch = "wasm32"))]
+pub use desktop_files::DesktopSettingsFiles;
+pub use env::EnvironmentVariable;
+#[cfg(not(target_arch = "wasm32"))]
+pub use gsettings::GSettings;
+#[cfg(not(target_arch = "wasm32"))]
+pub use schedule::{Clock, FakeClock, NextSwitch, Schedule, SystemClock, TimeOfDay};
+#[cfg(all(feature = "xdg-portal", target_os = "linux"))]
+pub use xdg_portal::{WatcherStatus, XdgPortal};
+
+/// Something that knows whether the system is in dark or light mode.
+pub trait SystemThemeSource: Send + Sync + 'static {
+    /// Returns the current system theme or `None` if this source has no opinion.
+    ///
+    /// This is called once per pass, so it should be cheap.
+    /// Sources that need to do expensive work should do so in the background.
+    fn system_theme(&self) -> Option<Theme>;
+
+    /// Returns the desktop's accent colour and contrast preference
+    /// or `None` if this source doesn't know about them.
+    fn desktop_appearance(&self) -> Option<DesktopAppearance> {
+        None
+    }
+
+    /// Called when the source is [registered](register).
+    /// Sources that watch for changes in the background use this
+    /// to request a repaint when something changes.
+    fn attach(&self, _ctx: &Context) {}
+}
+
+impl<S: SystemThemeSource + ?Sized> SystemThemeSource for Box<S> {
+    fn system_theme(&self) -> Option<Theme> {
+        (**self).system_theme()
+    }
+
+    fn desktop_appearance(&self) -> Option<DesktopAppearance> {
+        (**self).desktop_appearance()
+    }
+
+    fn attach(&self, ctx: &Context) {
+        (**self).attach(ctx);
+    }
+}
+
+impl<S: SystemThemeSource + ?Sized> SystemThemeSource for Arc<S> {
+    fn system_theme(&self) -> Option<Theme> {
+        (**self).system_theme()
+    }
+
+    fn desktop_appearance(&self) -> Option<DesktopAppearance> {
+        (**self).desktop_appearance()
+    }
+
+    fn attach(&self, ctx: &Context) {
+        (**self).attach(ctx);
+    }
+}
+
+/// Appearance preferences of the desktop beyond dark and light.
+///
+/// The theme switch picks these up automatically: the active indicator
+/// uses the accent colour, strokes get stronger when high contrast is requested
+/// and [transitions](crate::ThemeTransition) are skipped when motion should be reduced.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DesktopAppearance {
+    /// The accent colour picked by the user, if any.
+    pub accent_color: Option<Color32>,
+    pub contrast: Contrast,
+    /// Whether the user asked for animations to be reduced.
+    pub reduced_motion: bool,
+}
+
+/// The contrast level preferred by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Contrast {
+    #[default]
+    Normal,
+    High,
+}
+
+impl DesktopAppearance {
+    /// Reads the appearance last reported by a registered source.
+    pub fn load(ctx: &Context) -> Self {
+        ctx.data(|d| d.get_temp(Self::id())).unwrap_or_default()
+    }
+
+    pub fn store(self, ctx: &Context) {
+        ctx.data_mut(|d| d.insert_temp(Self::id(), self));
+    }
+
+    fn id() -> Id {
+        Id::new("egui_theme_switch::DesktopAppearance")
+    }
+}
+
+/// Makes egui's selection colours follow the desktop's accent colour.
+///
+/// This only has an effect when a [registered](register) source
+/// reports an accent colour. The original selection colours are restored
+/// when the accent colour goes away.
+pub fn follow_accent_color(ctx: &Context) {
+    ctx.on_begin_pass(
+        "follow_accent_color",
+        Arc::new(|ctx| {
+            let accent = DesktopAppearance::load(ctx).accent_color;
+            for theme in [Theme::Dark, Theme::Light] {
+                apply_accent_color(ctx, theme, accent);
+            }
+        }),
+    );
+}
+
+/// The selection colours of a theme before and after the accent colour was applied.
+#[derive(Debug, Clone, Copy)]
+struct AccentedSelection {
+    original: Selection,
+    accented: Selection,
+}
+
+fn apply_accent_color(ctx: &Context, theme: Theme, accent: Option<Color32>) {
+    let id = Id::new("egui_theme_switch::AccentedSelection").with(theme);
+    let selection = ctx.style_of(theme).visuals.selection;
+    let previous = ctx.data(|d| d.get_temp::<AccentedSelection>(id));
+    match accent {
+        Some(accent) => {
+            let accented = Selection {
+                bg_fill: accent,
+                stroke: Stroke::new(selection.stroke.width, contrasting_text_color(accent)),
+            };
+            if selection == accented {
+                return;
+            }
+            // Selection colours set by someone else since (e.g. a new style) are the new original.
+            let original = match previous {
+                Some(previous) if previous.accented == selection => previous.original,
+                _ => selection,
+            };
+            ctx.style_mut_of(theme, |style| style.visuals.selection = accented);
+            ctx.data_mut(|d| d.insert_temp(id, AccentedSelection { original, accented }));
+        }
+        None => {
+            let Some(previous) = previous else { return };
+            if previous.accented == selection {
+                ctx.style_mut_of(theme, |style| style.visuals.selection = previous.original);
+            }
+            ctx.data_mut(|d| d.remove::<AccentedSelection>(id));
+        }
+    }
+}
+
+/// Picks black or white, whichever is more legible on `background`.
+pub(crate) fn contrasting_text_color(background: Color32) -> Color32 {
+    let [r, g, b, _] = egui::Rgba::from(background).to_array();
+    let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    if luminance > 0.18 {
+        Color32::BLACK
+    } else {
+        Color32::WHITE
+    }
+}
+
+/// Attaches `source` to the context and stores the [`DesktopAppearance`]
+/// it reports at the start of each pass.
+///
+/// egui latches the system theme from the raw input before any plugin runs,
+/// so registering a source doesn't change the theme: use [`feed`] with the same source
+/// (share it with an [`Arc`] or a clone) to apply it.
+pub fn register(ctx: &Context, source: impl SystemThemeSource) {
+    source.attach(ctx);
+    ctx.on_begin_pass(
+        "update_desktop_appearance",
+        Arc::new(move |ctx| {
+            if let Some(appearance) = source.desktop_appearance() {
+                appearance.store(ctx);
+            }
+        }),
+    );
+}
+
+/// Feeds the system theme from `source` into the raw input before it's passed to egui.
+///
+/// The theme reported by the integration is kept if `source` has no opinion.
+pub fn feed(raw_input: &mut RawInput, source: &impl SystemThemeSource) {
+    if let Some(theme) = source.system_theme() {
+        raw_input.system_theme = Some(theme);
+    }
+}
+
+/// Asks each source in turn and uses the first theme that is reported.
+///
+/// ```
+/// use egui_theme_switch::system_theme::{EnvironmentVariable, ManualOverride, PriorityChain};
+///
+/// let manual = ManualOverride::default();
+/// let chain = PriorityChain::new()
+///     .with(manual.clone())
+///     .with(EnvironmentVariable::gtk_theme());
+/// ```
+#[derive(Default)]
+pub struct PriorityChain {
+    sources: Vec<Box<dyn SystemThemeSource>>,
+}
+
+impl PriorityChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a source with lower priority than all previously added sources.
+    pub fn with(mut self, source: impl SystemThemeSource) -> Self {
+        self.sources.push(Box::new(source));
+        self
+    }
+}
+
+impl SystemThemeSource for PriorityChain {
+    fn system_theme(&self) -> Option<Theme> {
+        self.sources.iter().find_map(|s| s.system_theme())
+    }
+
+    fn desktop_appearance(&self) -> Option<DesktopAppearance> {
+        self.sources.iter().find_map(|s| s.desktop_appearance())
+    }
+
+    fn attach(&self, ctx: &Context) {
+        for source in &self.sources {
+            source.attach(ctx);
+        }
+    }
+}
+
+impl fmt::Debug for PriorityChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PriorityChain")
+            .field("sources", &self.sources.len())
+            .finish()
+    }
+}
+
+/// A theme chosen by the user or the app, e.g. from a settings page.
+/// Clones share the same value.
+#[derive(Debug, Clone, Default)]
+pub struct ManualOverride(Arc<RwLock<Option<Theme>>>);
+
+impl ManualOverride {
+    pub fn new(theme: Option<Theme>) -> Self {
+        Self(Arc::new(RwLock::new(theme)))
+    }
+
+    /// Overrides the system theme, `None` removes the override.
+    pub fn set(&self, theme: Option<Theme>) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = theme;
+    }
+
+    pub fn get(&self) -> Option<Theme> {
+        *self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl SystemThemeSource for ManualOverride {
+    fn system_theme(&self) -> Option<Theme> {
+        self.get()
+    }
+}
+
+/// A source for tests that reports whatever it was last [`set`](Self::set) to
+/// and keeps track of how often it was asked.
+/// Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct FakeSource {
+    theme: ManualOverride,
+    queries: Arc<AtomicUsize>,
+}
+
+impl FakeSource {
+    pub fn new(theme: Option<Theme>) -> Self {
+        Self {
+            theme: ManualOverride::new(theme),
+            queries: Arc::default(),
+        }
+    }
+
+    pub fn set(&self, theme: Option<Theme>) {
+        self.theme.set(theme);
+    }
+
+    /// How many times this source was asked for the system theme.
+    pub fn queries(&self) -> usize {
+        self.queries.load(Ordering::Relaxed)
+    }
+}
+
+impl SystemThemeSource for FakeSource {
+    fn system_theme(&self) -> Option<Theme> {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        self.theme.get()
+    }
+}
+
+/// Parses `dark` or `light` (ignoring case and surrounding whitespace).
+pub(crate) fn parse_theme_name(value: &str) -> Option<Theme> {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("dark") {
+        Some(Theme::Dark)
+    } else if value.eq_ignore_ascii_case("light") {
+        Some(Theme::Light)
+    } else {
+        None
+    }
+}
+
+/// Parses a GTK theme, which is specified as `name[:variant]` (e.g. `Adwaita:dark`).
+///
+/// Themes without a variant are light unless their name says otherwise (e.g. `Adwaita-dark`).
+/// Returns `None` for an empty name.
+///
+/// ```
+/// use egui::Theme;
+/// use egui_theme_switch::system_theme::parse_gtk_theme;
+///
+/// assert_eq!(parse_gtk_theme("Adwaita:dark"), Some(Theme::Dark));
+/// assert_eq!(parse_gtk_theme("Adwaita-dark"), Some(Theme::Dark));
+/// assert_eq!(parse_gtk_theme("Adwaita"), Some(Theme::Light));
+/// assert_eq!(parse_gtk_theme(""), None);
+/// ```
+pub fn parse_gtk_theme(value: &str) -> Option<Theme> {
+    let (name, variant) = match value.split_once(':') {
+        Some((name, variant)) => (name, Some(variant)),
+        None => (value, None),
+    };
+    if name.trim().is_empty() {
+        return None;
+    }
+    variant.and_then(parse_theme_name).or_else(|| {
+        if name.to_ascii_lowercase().contains("dark") {
+            Some(Theme::Dark)
+        } else {
+            Some(Theme::Light)
+        }
+    })
+}
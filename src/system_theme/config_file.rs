@@ -0,0 +1,50 @@
+use super::polling::Poller;
+use super::{parse_theme_name, SystemThemeSource};
+use egui::{Context, Theme};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Reads the system theme from a file, e.g. one that is
+/// written by a script that runs when the desktop switches themes.
+///
+/// By default the file should contain `dark` or `light`.
+/// The file is re-read in the background so that changes are picked up.
+#[derive(Debug)]
+pub struct ConfigFile {
+    poller: Poller,
+}
+
+impl ConfigFile {
+    /// Reads `dark` or `light` from the file every second.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self::with_parser(path, parse_theme_name)
+    }
+
+    /// Reads the file using a custom parser.
+    pub fn with_parser(path: impl Into<PathBuf>, parse: fn(&str) -> Option<Theme>) -> Self {
+        Self::with_parser_and_interval(path, parse, Duration::from_secs(1))
+    }
+
+    pub fn with_parser_and_interval(
+        path: impl Into<PathBuf>,
+        parse: fn(&str) -> Option<Theme>,
+        interval: Duration,
+    ) -> Self {
+        let path = path.into();
+        let poll = move || fs::read_to_string(&path).ok().and_then(|s| parse(&s));
+        Self {
+            poller: Poller::spawn("config-file-theme", interval, poll),
+        }
+    }
+}
+
+impl SystemThemeSource for ConfigFile {
+    fn system_theme(&self) -> Option<Theme> {
+        self.poller.get()
+    }
+
+    fn attach(&self, ctx: &Context) {
+        self.poller.attach(ctx);
+    }
+}
@@ -0,0 +1,203 @@
+use super::polling::Poller;
+use super::SystemThemeSource;
+use egui::{Context, Theme};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Reads the theme from the settings files written by GTK and KDE
+/// for systems where the XDG desktop portal isn't available
+/// (e.g. minimal window managers or containers).
+///
+/// The following files are consulted in order:
+/// * `gtk-4.0/settings.ini` and `gtk-3.0/settings.ini`
+///   (`gtk-application-prefer-dark-theme` and `gtk-theme-name`)
+/// * `kdeglobals` (the `ColorScheme` name or the window background colour)
+///
+/// The files are watched for changes in the background.
+///
+/// ```
+/// use egui::Theme;
+/// use egui_theme_switch::system_theme::DesktopSettingsFiles;
+/// use std::fs;
+///
+/// let config_dir = tempfile::tempdir().unwrap();
+/// fs::create_dir(config_dir.path().join("gtk-3.0")).unwrap();
+/// fs::write(
+///     config_dir.path().join("gtk-3.0/settings.ini"),
+///     "[Settings]\ngtk-application-prefer-dark-theme=1\n",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(DesktopSettingsFiles::read_theme(config_dir.path()), Some(Theme::Dark));
+/// ```
+#[derive(Debug)]
+pub struct DesktopSettingsFiles {
+    /// `None` if there's no config directory to watch.
+    poller: Option<Poller>,
+}
+
+impl DesktopSettingsFiles {
+    /// Watches the files in the user's config directory
+    /// (`$XDG_CONFIG_HOME` or `~/.config`).
+    ///
+    /// If neither is set, the source has no opinion.
+    pub fn new() -> Self {
+        match default_config_dir() {
+            Some(config_dir) => Self::with_config_dir(config_dir),
+            None => Self { poller: None },
+        }
+    }
+
+    /// Watches the files in the given directory instead of the user's config directory.
+    pub fn with_config_dir(config_dir: impl Into<PathBuf>) -> Self {
+        Self::with_config_dir_and_interval(config_dir, Duration::from_secs(1))
+    }
+
+    pub fn with_config_dir_and_interval(
+        config_dir: impl Into<PathBuf>,
+        interval: Duration,
+    ) -> Self {
+        let config_dir = config_dir.into();
+        let mut watched = WatchedFiles::default();
+        let poll = move || watched.theme(&config_dir);
+        Self {
+            poller: Some(Poller::spawn("desktop-files-theme", interval, poll)),
+        }
+    }
+
+    /// Reads the theme from the files in `config_dir` once.
+    pub fn read_theme(config_dir: &Path) -> Option<Theme> {
+        files(config_dir).into_iter().find_map(|(path, parse)| {
+            let contents = fs::read_to_string(path).ok()?;
+            parse(&contents)
+        })
+    }
+}
+
+impl Default for DesktopSettingsFiles {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemThemeSource for DesktopSettingsFiles {
+    fn system_theme(&self) -> Option<Theme> {
+        self.poller.as_ref()?.get()
+    }
+
+    fn attach(&self, ctx: &Context) {
+        if let Some(poller) = &self.poller {
+            poller.attach(ctx);
+        }
+    }
+}
+
+type Parser = fn(&str) -> Option<Theme>;
+
+fn files(config_dir: &Path) -> [(PathBuf, Parser); 3] {
+    [
+        (config_dir.join("gtk-4.0/settings.ini"), parse_gtk_settings),
+        (config_dir.join("gtk-3.0/settings.ini"), parse_gtk_settings),
+        (config_dir.join("kdeglobals"), parse_kdeglobals),
+    ]
+}
+
+fn default_config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+}
+
+/// Only re-reads the files when one of them was modified.
+#[derive(Debug, Default)]
+struct WatchedFiles {
+    modified: Option<Vec<Option<SystemTime>>>,
+    theme: Option<Theme>,
+}
+
+impl WatchedFiles {
+    fn theme(&mut self, config_dir: &Path) -> Option<Theme> {
+        let modified = files(config_dir)
+            .iter()
+            .map(|(path, _)| fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect();
+        if self.modified.as_ref() != Some(&modified) {
+            self.theme = DesktopSettingsFiles::read_theme(config_dir);
+            self.modified = Some(modified);
+        }
+        self.theme
+    }
+}
+
+fn parse_gtk_settings(contents: &str) -> Option<Theme> {
+    let prefer_dark = ini_value(contents, "Settings", "gtk-application-prefer-dark-theme");
+    let theme_name = ini_value(contents, "Settings", "gtk-theme-name");
+    if prefer_dark.is_some_and(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        || theme_name.is_some_and(is_dark_name)
+    {
+        Some(Theme::Dark)
+    } else if prefer_dark.is_some() || theme_name.is_some() {
+        Some(Theme::Light)
+    } else {
+        None
+    }
+}
+
+fn parse_kdeglobals(contents: &str) -> Option<Theme> {
+    // Custom colour schemes don't necessarily say "dark" in their name,
+    // so we fall back to looking at the window background.
+    let color_scheme = ini_value(contents, "General", "ColorScheme");
+    if color_scheme.is_some_and(is_dark_name) {
+        return Some(Theme::Dark);
+    }
+    ini_value(contents, "Colors:Window", "BackgroundNormal")
+        .and_then(parse_rgb)
+        .map(theme_for_background)
+        .or(color_scheme.map(|_| Theme::Light))
+}
+
+fn is_dark_name(name: &str) -> bool {
+    name.to_ascii_lowercase().contains("dark")
+}
+
+fn parse_rgb(value: &str) -> Option<[u8; 3]> {
+    let mut components = value.split(',').map(|c| c.trim().parse::<u8>().ok());
+    let rgb = [
+        components.next()??,
+        components.next()??,
+        components.next()??,
+    ];
+    Some(rgb)
+}
+
+fn theme_for_background([r, g, b]: [u8; 3]) -> Theme {
+    // Relative luminance (without gamma correction, this is good enough to tell dark from light).
+    let luminance = 0.2126 * f32::from(r) + 0.7152 * f32::from(g) + 0.0722 * f32::from(b);
+    if luminance < 128.0 {
+        Theme::Dark
+    } else {
+        Theme::Light
+    }
+}
+
+/// Finds the value for `key` in `section` of an INI file.
+fn ini_value<'a>(contents: &'a str, section: &str, key: &str) -> Option<&'a str> {
+    let mut in_section = false;
+    for line in contents.lines().map(str::trim) {
+        if line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name == section;
+        } else if in_section {
+            if let Some((k, v)) = line.split_once('=') {
+                if k.trim() == key {
+                    return Some(v.trim());
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,47 @@
+use super::{parse_gtk_theme, parse_theme_name, SystemThemeSource};
+use egui::Theme;
+use std::borrow::Cow;
+
+/// Reads the system theme from an environment variable.
+///
+/// ```
+/// use egui_theme_switch::system_theme::EnvironmentVariable;
+///
+/// // Uses `dark` or `light` from `MY_APP_THEME`:
+/// let source = EnvironmentVariable::new("MY_APP_THEME");
+/// ```
+#[derive(Debug, Clone)]
+pub struct EnvironmentVariable {
+    name: Cow<'static, str>,
+    parse: fn(&str) -> Option<Theme>,
+}
+
+impl EnvironmentVariable {
+    /// Reads `dark` or `light` from the given variable.
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self::with_parser(name, parse_theme_name)
+    }
+
+    /// Reads the variable using a custom parser.
+    pub fn with_parser(
+        name: impl Into<Cow<'static, str>>,
+        parse: fn(&str) -> Option<Theme>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            parse,
+        }
+    }
+
+    /// Reads GTK's theme override, e.g. `GTK_THEME=Adwaita:dark`.
+    pub fn gtk_theme() -> Self {
+        Self::with_parser("GTK_THEME", parse_gtk_theme)
+    }
+}
+
+impl SystemThemeSource for EnvironmentVariable {
+    fn system_theme(&self) -> Option<Theme> {
+        let value = std::env::var(&*self.name).ok()?;
+        (self.parse)(&value)
+    }
+}
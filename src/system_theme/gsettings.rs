@@ -0,0 +1,71 @@
+use super::polling::Poller;
+use super::{parse_gtk_theme, SystemThemeSource};
+use egui::{Context, Theme};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Reads GNOME's color scheme preference using the `gsettings` command.
+///
+/// The `gsettings` command is polled in the background.
+#[derive(Debug)]
+pub struct GSettings {
+    poller: Poller,
+}
+
+impl GSettings {
+    /// Polls `gsettings` every two seconds.
+    pub fn new() -> Self {
+        Self::with_interval(Duration::from_secs(2))
+    }
+
+    pub fn with_interval(interval: Duration) -> Self {
+        Self {
+            poller: Poller::spawn("gsettings-theme", interval, query),
+        }
+    }
+}
+
+impl Default for GSettings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemThemeSource for GSettings {
+    fn system_theme(&self) -> Option<Theme> {
+        self.poller.get()
+    }
+
+    fn attach(&self, ctx: &Context) {
+        self.poller.attach(ctx);
+    }
+}
+
+fn query() -> Option<Theme> {
+    // `color-scheme` is only available since GNOME 42,
+    // older versions only have the theme name to go by.
+    match get("color-scheme") {
+        Some(color_scheme) => parse_color_scheme(&color_scheme),
+        None => get("gtk-theme").and_then(|theme| parse_gtk_theme(&theme)),
+    }
+}
+
+fn get(key: &str) -> Option<String> {
+    let output = Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", key])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let value = String::from_utf8(output.stdout).ok()?;
+    Some(value.trim().trim_matches('\'').to_owned())
+}
+
+fn parse_color_scheme(value: &str) -> Option<Theme> {
+    match value {
+        "prefer-dark" => Some(Theme::Dark),
+        "prefer-light" => Some(Theme::Light),
+        _ => None,
+    }
+}
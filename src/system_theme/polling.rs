@@ -0,0 +1,72 @@
+use egui::{Context, Theme};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::thread;
+use std::time::Duration;
+
+/// Polls for the theme on a background thread.
+/// The thread exits once the poller is dropped.
+#[derive(Debug)]
+pub(crate) struct Poller {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    theme: RwLock<Option<Theme>>,
+    ctx: RwLock<Option<Context>>,
+}
+
+impl Poller {
+    pub(crate) fn spawn(
+        name: &str,
+        interval: Duration,
+        mut poll: impl FnMut() -> Option<Theme> + Send + 'static,
+    ) -> Self {
+        // The first poll happens right away so that the first pass already has a theme.
+        let shared = Arc::new(Shared {
+            theme: RwLock::new(poll()),
+            ctx: RwLock::default(),
+        });
+        let weak = Arc::downgrade(&shared);
+        // If we can't spawn a thread we're stuck with the initial value, which is fine.
+        _ = thread::Builder::new()
+            .name(name.to_owned())
+            .spawn(move || loop {
+                thread::sleep(interval);
+                let value = poll();
+                let Some(shared) = weak.upgrade() else { break };
+                shared.set(value);
+            });
+        Self { shared }
+    }
+
+    pub(crate) fn get(&self) -> Option<Theme> {
+        *self
+            .shared
+            .theme
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn attach(&self, ctx: &Context) {
+        *self
+            .shared
+            .ctx
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(ctx.clone());
+    }
+}
+
+impl Shared {
+    fn set(&self, value: Option<Theme>) {
+        let old = std::mem::replace(
+            &mut *self.theme.write().unwrap_or_else(PoisonError::into_inner),
+            value,
+        );
+        if old != value {
+            if let Some(ctx) = &*self.ctx.read().unwrap_or_else(PoisonError::into_inner) {
+                ctx.request_repaint();
+            }
+        }
+    }
+}
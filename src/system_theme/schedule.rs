@@ -0,0 +1,432 @@
+use super::SystemThemeSource;
+use egui::{Context, Id, Theme};
+use std::f64::consts::PI;
+use std::fmt;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Switches between light and dark depending on the time of day,
+/// either at fixed clock times or at sunrise and sunset.
+///
+/// Sunrise and sunset are calculated offline from the configured coordinates.
+/// When registered, the switch's "Follow System" tooltip shows the next switch time
+/// (also while a source with higher priority decides the theme).
+///
+/// Times are in a time zone given as a fixed offset from UTC.
+/// Daylight saving time isn't applied automatically: when the offset changes,
+/// update it with [`Schedule::set_utc_offset_minutes`].
+/// Clones share the clock and the offset.
+///
+/// Schedules aren't available on the web, where the system clock can't be read.
+///
+/// ```
+/// use egui::Theme;
+/// use egui_theme_switch::system_theme::{FakeClock, Schedule, SystemThemeSource, TimeOfDay};
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let clock = FakeClock::new(UNIX_EPOCH + Duration::from_secs(12 * 3600));
+/// let schedule = Schedule::fixed(TimeOfDay::new(7, 0), TimeOfDay::new(19, 0), 0)
+///     .with_clock(clock.clone());
+/// assert_eq!(schedule.system_theme(), Some(Theme::Light));
+///
+/// clock.advance(Duration::from_secs(8 * 3600));
+/// assert_eq!(schedule.system_theme(), Some(Theme::Dark));
+///
+/// let next = schedule.next_switch_after(clock.now()).unwrap();
+/// assert_eq!((next.theme, next.local_time), (Theme::Light, TimeOfDay::new(7, 0)));
+/// ```
+#[derive(Clone)]
+pub struct Schedule {
+    kind: ScheduleKind,
+    /// In minutes.
+    utc_offset: Arc<AtomicI32>,
+    clock: Arc<dyn Clock>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ScheduleKind {
+    Fixed { light: TimeOfDay, dark: TimeOfDay },
+    Sun { latitude: f64, longitude: f64 },
+}
+
+/// A time on the clock, e.g. `07:30`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeOfDay {
+    hour: u8,
+    minute: u8,
+}
+
+/// When the [`Schedule`] switches themes next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NextSwitch {
+    pub at: SystemTime,
+    /// [`NextSwitch::at`] in the schedule's time zone.
+    pub local_time: TimeOfDay,
+    /// The theme that will be used after the switch.
+    pub theme: Theme,
+}
+
+/// Where a [`Schedule`] gets the current time from.
+pub trait Clock: Send + Sync + 'static {
+    fn now(&self) -> SystemTime;
+}
+
+/// The real system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+/// A clock for tests that only moves when told to.
+/// Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct FakeClock(Arc<RwLock<SystemTime>>);
+
+impl Schedule {
+    /// Uses the light theme from `light` until `dark`, both in the time zone
+    /// that is `utc_offset_minutes` ahead of UTC.
+    pub fn fixed(light: TimeOfDay, dark: TimeOfDay, utc_offset_minutes: i32) -> Self {
+        Self::new(ScheduleKind::Fixed { light, dark }, utc_offset_minutes)
+    }
+
+    /// Uses the light theme between sunrise and sunset at the given coordinates
+    /// (in degrees, north and east are positive).
+    /// Switch times are shown in the time zone that is `utc_offset_minutes` ahead of UTC.
+    ///
+    /// ```
+    /// use egui::Theme;
+    /// use egui_theme_switch::system_theme::{Schedule, TimeOfDay};
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// // Zurich on the 21st of June 2024 at midnight, in CEST:
+    /// let schedule = Schedule::sun(47.37, 8.54, 120);
+    /// let midnight = UNIX_EPOCH + Duration::from_secs(1_718_920_800);
+    /// assert_eq!(schedule.theme_at(midnight), Theme::Dark);
+    ///
+    /// let sunrise = schedule.next_switch_after(midnight).unwrap();
+    /// assert_eq!((sunrise.theme, sunrise.local_time), (Theme::Light, TimeOfDay::new(5, 29)));
+    /// ```
+    pub fn sun(latitude: f64, longitude: f64, utc_offset_minutes: i32) -> Self {
+        let kind = ScheduleKind::Sun {
+            latitude,
+            longitude,
+        };
+        Self::new(kind, utc_offset_minutes)
+    }
+
+    fn new(kind: ScheduleKind, utc_offset_minutes: i32) -> Self {
+        Self {
+            kind,
+            utc_offset: Arc::new(AtomicI32::new(utc_offset_minutes)),
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Changes the time zone, e.g. when daylight saving time starts or ends.
+    pub fn set_utc_offset_minutes(&self, minutes: i32) {
+        self.utc_offset.store(minutes, Ordering::Relaxed);
+    }
+
+    pub fn utc_offset_minutes(&self) -> i32 {
+        self.utc_offset.load(Ordering::Relaxed)
+    }
+
+    /// The theme that is used at the given time.
+    pub fn theme_at(&self, time: SystemTime) -> Theme {
+        let time = unix_seconds(time);
+        match self.kind {
+            ScheduleKind::Fixed { light, dark } => {
+                let now = self.local_seconds_of_day(time);
+                let light = light.seconds();
+                let dark = dark.seconds();
+                let is_light = if light <= dark {
+                    (light..dark).contains(&now)
+                } else {
+                    !(dark..light).contains(&now)
+                };
+                if is_light {
+                    Theme::Light
+                } else {
+                    Theme::Dark
+                }
+            }
+            ScheduleKind::Sun {
+                latitude,
+                longitude,
+            } => sun_events_around(time, latitude, longitude)
+                .into_iter()
+                .rev()
+                .find(|e| e.time <= time)
+                .map(|e| e.theme)
+                .unwrap_or_else(|| polar_theme(time, latitude, longitude)),
+        }
+    }
+
+    /// The first switch after the given time, if there is one in the next few days.
+    pub fn next_switch_after(&self, time: SystemTime) -> Option<NextSwitch> {
+        let time = unix_seconds(time);
+        let (at, theme) = match self.kind {
+            ScheduleKind::Fixed { light, dark } => {
+                let now = self.local_seconds_of_day(time);
+                // A switch that happens right now is already in effect,
+                // so the next one is a day later.
+                let until = |t: TimeOfDay| match (t.seconds() - now).rem_euclid(SECONDS_PER_DAY) {
+                    0.0 => SECONDS_PER_DAY,
+                    seconds => seconds,
+                };
+                if until(light) < until(dark) {
+                    (time + until(light), Theme::Light)
+                } else {
+                    (time + until(dark), Theme::Dark)
+                }
+            }
+            ScheduleKind::Sun {
+                latitude,
+                longitude,
+            } => sun_events_around(time, latitude, longitude)
+                .into_iter()
+                .find(|e| e.time > time)
+                .map(|e| (e.time, e.theme))?,
+        };
+        Some(NextSwitch {
+            at: from_unix_seconds(at),
+            local_time: TimeOfDay::from_seconds(self.local_seconds_of_day(at)),
+            theme,
+        })
+    }
+
+    fn local_seconds_of_day(&self, unix_seconds: f64) -> f64 {
+        let utc_offset = f64::from(self.utc_offset_minutes()) * 60.0;
+        (unix_seconds + utc_offset).rem_euclid(SECONDS_PER_DAY)
+    }
+
+    /// Stores the next switch for the tooltip and repaints when it's due.
+    fn update_next_switch(&self, ctx: &Context) {
+        let now = self.clock.now();
+        let next_switch = self.next_switch_after(now);
+        if let Some(next_switch) = next_switch {
+            let delay = next_switch.at.duration_since(now).unwrap_or_default();
+            ctx.request_repaint_after(delay);
+        }
+        NextSwitch::store(next_switch, ctx);
+    }
+}
+
+impl SystemThemeSource for Schedule {
+    fn system_theme(&self) -> Option<Theme> {
+        Some(self.theme_at(self.clock.now()))
+    }
+
+    // The next switch is updated at the start of each pass, independent of
+    // whether this source is asked, e.g. in a chain below a source with an opinion.
+    fn attach(&self, ctx: &Context) {
+        let schedule = self.clone();
+        ctx.on_begin_pass(
+            "update_next_switch",
+            Arc::new(move |ctx| schedule.update_next_switch(ctx)),
+        );
+    }
+}
+
+impl fmt::Debug for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Schedule")
+            .field("kind", &self.kind)
+            .field("utc_offset", &self.utc_offset_minutes())
+            .finish_non_exhaustive()
+    }
+}
+
+impl NextSwitch {
+    /// Reads the next switch of the registered [`Schedule`].
+    pub fn load(ctx: &Context) -> Option<Self> {
+        ctx.data(|d| d.get_temp(Self::id()))
+    }
+
+    fn store(next_switch: Option<Self>, ctx: &Context) {
+        ctx.data_mut(|d| match next_switch {
+            Some(next_switch) => d.insert_temp(Self::id(), next_switch),
+            None => d.remove::<Self>(Self::id()),
+        });
+    }
+
+    fn id() -> Id {
+        Id::new("egui_theme_switch::NextSwitch")
+    }
+}
+
+impl TimeOfDay {
+    /// # Panics
+    /// Panics if `hour` or `minute` are out of range.
+    pub const fn new(hour: u8, minute: u8) -> Self {
+        assert!(hour < 24 && minute < 60, "time of day out of range");
+        Self { hour, minute }
+    }
+
+    pub fn hour(self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(self) -> u8 {
+        self.minute
+    }
+
+    fn seconds(self) -> f64 {
+        f64::from(self.hour) * 3600.0 + f64::from(self.minute) * 60.0
+    }
+
+    fn from_seconds(seconds: f64) -> Self {
+        let minutes = (seconds / 60.0).round() as u32 % (24 * 60);
+        Self {
+            hour: (minutes / 60) as u8,
+            minute: (minutes % 60) as u8,
+        }
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+impl FakeClock {
+    pub fn new(now: SystemTime) -> Self {
+        Self(Arc::new(RwLock::new(now)))
+    }
+
+    pub fn set(&self, now: SystemTime) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = now;
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) += duration;
+    }
+
+    pub fn now(&self) -> SystemTime {
+        *self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> SystemTime {
+        FakeClock::now(self)
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> f64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs_f64(),
+        Err(e) => -e.duration().as_secs_f64(),
+    }
+}
+
+fn from_unix_seconds(seconds: f64) -> SystemTime {
+    if seconds >= 0.0 {
+        UNIX_EPOCH + Duration::from_secs_f64(seconds)
+    } else {
+        UNIX_EPOCH - Duration::from_secs_f64(-seconds)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SunEvent {
+    time: f64,
+    theme: Theme,
+}
+
+// Sunrises and sunsets from the day before until the day after `time`, in order.
+fn sun_events_around(time: f64, latitude: f64, longitude: f64) -> Vec<SunEvent> {
+    let day = julian_day(time);
+    let mut events: Vec<_> = (-1..=1)
+        .filter_map(|offset| sunrise_and_sunset(day + f64::from(offset), latitude, longitude))
+        .flat_map(|(rise, set)| {
+            [
+                SunEvent {
+                    time: rise,
+                    theme: Theme::Light,
+                },
+                SunEvent {
+                    time: set,
+                    theme: Theme::Dark,
+                },
+            ]
+        })
+        .collect();
+    events.sort_by(|a, b| a.time.total_cmp(&b.time));
+    events
+}
+
+// The sun doesn't rise or set during polar day and night.
+fn polar_theme(time: f64, latitude: f64, longitude: f64) -> Theme {
+    if hour_angle_cosine(julian_day(time), latitude, longitude) < -1.0 {
+        Theme::Light
+    } else {
+        Theme::Dark
+    }
+}
+
+const J2000: f64 = 2_451_545.0;
+const UNIX_EPOCH_JULIAN_DAY: f64 = 2_440_587.5;
+
+fn julian_day(unix_seconds: f64) -> f64 {
+    unix_seconds / SECONDS_PER_DAY + UNIX_EPOCH_JULIAN_DAY
+}
+
+// Implementation of the sunrise equation, see:
+// https://en.wikipedia.org/wiki/Sunrise_equation#Complete_calculation_on_Earth
+
+struct SolarDay {
+    transit: f64,
+    declination: f64,
+}
+
+fn solar_day(julian_day: f64, longitude: f64) -> SolarDay {
+    let n = (julian_day - J2000 + 0.0008).ceil();
+    let mean_solar_time = n - longitude / 360.0;
+    let mean_anomaly = (357.5291 + 0.985_600_28 * mean_solar_time).rem_euclid(360.0);
+    let m = mean_anomaly.to_radians();
+    let center = 1.9148 * m.sin() + 0.02 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+    let ecliptic_longitude = (mean_anomaly + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+    let transit =
+        J2000 + mean_solar_time + 0.0053 * m.sin() - 0.0069 * (2.0 * ecliptic_longitude).sin();
+    let declination = (ecliptic_longitude.sin() * 23.4397_f64.to_radians().sin()).asin();
+    SolarDay {
+        transit,
+        declination,
+    }
+}
+
+fn hour_angle_cosine(julian_day: f64, latitude: f64, longitude: f64) -> f64 {
+    let SolarDay { declination, .. } = solar_day(julian_day, longitude);
+    let latitude = latitude.to_radians();
+    // -0.833° accounts for refraction and the size of the sun's disc.
+    let altitude = (-0.833_f64).to_radians();
+    (altitude.sin() - latitude.sin() * declination.sin()) / (latitude.cos() * declination.cos())
+}
+
+fn sunrise_and_sunset(julian_day: f64, latitude: f64, longitude: f64) -> Option<(f64, f64)> {
+    let SolarDay { transit, .. } = solar_day(julian_day, longitude);
+    let cos_hour_angle = hour_angle_cosine(julian_day, latitude, longitude);
+    if !(-1.0..=1.0).contains(&cos_hour_angle) {
+        return None;
+    }
+    // The hour angle as a fraction of a day.
+    let hour_angle = cos_hour_angle.acos() / (2.0 * PI);
+    let to_unix = |jd: f64| (jd - UNIX_EPOCH_JULIAN_DAY) * SECONDS_PER_DAY;
+    Some((to_unix(transit - hour_angle), to_unix(transit + hour_angle)))
+}
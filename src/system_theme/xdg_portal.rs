@@ -0,0 +1,311 @@
+use super::{Contrast, DesktopAppearance, SystemThemeSource};
+use ashpd::desktop::settings::{self, ColorScheme, Settings};
+use ashpd::desktop::Color;
+use async_io::Timer;
+use egui::{Color32, Context, Theme};
+use futures_lite::{future, stream, StreamExt as _};
+use std::fmt;
+use std::future::Future;
+use std::pin::{pin, Pin};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard};
+use std::thread;
+use std::time::Duration;
+
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+// There's no standard key for reduced motion yet, but GNOME exposes its own setting through the portal.
+const GNOME_INTERFACE_NAMESPACE: &str = "org.gnome.desktop.interface";
+const ENABLE_ANIMATIONS_KEY: &str = "enable-animations";
+
+/// Changes arriving within this interval of each other are combined into one update.
+const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(100);
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Reads the color scheme, accent colour, contrast and reduced motion preference
+/// from the [XDG settings portal](https://flatpak.github.io/xdg-desktop-portal/docs/doc-org.freedesktop.portal.Settings.html).
+///
+/// The portal is watched for changes in the background (on a dedicated thread by default,
+/// see [`XdgPortal::with_spawner`] for other options). The watcher reconnects (with backoff)
+/// when the portal is restarted or can't be reached and requests a repaint whenever something changes.
+/// Use [`XdgPortal::status`] to find out why the system theme isn't followed.
+///
+/// The watcher is stopped when this source is dropped.
+#[derive(Debug)]
+pub struct XdgPortal {
+    shared: Arc<Shared>,
+    // Dropping the sender tells the watcher to stop.
+    _stop: async_channel::Sender<()>,
+}
+
+/// What the [`XdgPortal`] watcher is currently up to.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum WatcherStatus {
+    #[default]
+    Connecting,
+    /// Connected to the portal and listening for changes.
+    Active,
+    /// There's no portal implementation on this system.
+    Unavailable,
+    /// Connecting to the portal failed, the watcher will retry.
+    Error(String),
+}
+
+impl fmt::Display for WatcherStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WatcherStatus::Connecting => write!(f, "connecting to the desktop portal"),
+            WatcherStatus::Active => write!(f, "following the desktop portal"),
+            WatcherStatus::Unavailable => write!(f, "the desktop portal is not available"),
+            WatcherStatus::Error(error) => write!(f, "desktop portal error: {error}"),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    state: RwLock<PortalState>,
+    ctx: RwLock<Option<Context>>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct PortalState {
+    status: WatcherStatus,
+    theme: Option<Theme>,
+    appearance: Option<DesktopAppearance>,
+}
+
+impl XdgPortal {
+    /// Watches the portal on a dedicated background thread,
+    /// no async runtime is needed.
+    pub fn new() -> Self {
+        Self::with_spawner(|watcher| {
+            // If we can't spawn a thread we never report anything, which is fine.
+            _ = thread::Builder::new()
+                .name("xdg-portal-theme".to_owned())
+                .spawn(move || async_io::block_on(watcher));
+        })
+    }
+
+    /// Watches the portal on an async runtime of your choice.
+    /// `spawn` is called once with a future that should be run to completion.
+    ///
+    /// ```no_run
+    /// use egui_theme_switch::system_theme::XdgPortal;
+    ///
+    /// let portal = XdgPortal::with_spawner(|watcher| {
+    ///     std::thread::spawn(|| futures_lite::future::block_on(watcher));
+    /// });
+    /// ```
+    pub fn with_spawner(spawn: impl FnOnce(Pin<Box<dyn Future<Output = ()> + Send>>)) -> Self {
+        let shared = Arc::<Shared>::default();
+        let (stop, stopped) = async_channel::bounded(1);
+        let watcher = {
+            let shared = shared.clone();
+            async move { future::or(watch(&shared), stop_signal(stopped)).await }
+        };
+        spawn(Box::pin(watcher));
+        Self {
+            shared,
+            _stop: stop,
+        }
+    }
+
+    /// Watches the portal on a tokio runtime.
+    #[cfg(feature = "tokio")]
+    pub fn spawn_on_tokio(handle: &tokio::runtime::Handle) -> Self {
+        Self::with_spawner(|watcher| {
+            handle.spawn(watcher);
+        })
+    }
+
+    /// Watches the portal on async-std's global executor.
+    #[cfg(feature = "async-std")]
+    pub fn spawn_on_async_std() -> Self {
+        Self::with_spawner(|watcher| {
+            async_std::task::spawn(watcher);
+        })
+    }
+
+    pub fn status(&self) -> WatcherStatus {
+        self.shared.state().status.clone()
+    }
+}
+
+impl Default for XdgPortal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SystemThemeSource for XdgPortal {
+    fn system_theme(&self) -> Option<Theme> {
+        self.shared.state().theme
+    }
+
+    fn desktop_appearance(&self) -> Option<DesktopAppearance> {
+        self.shared.state().appearance
+    }
+
+    fn attach(&self, ctx: &Context) {
+        *self
+            .shared
+            .ctx
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(ctx.clone());
+    }
+}
+
+impl Shared {
+    fn state(&self) -> RwLockReadGuard<'_, PortalState> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn update(&self, f: impl FnOnce(&mut PortalState)) {
+        let changed = {
+            let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+            let old = state.clone();
+            f(&mut state);
+            *state != old
+        };
+        if changed {
+            self.request_repaint();
+        }
+    }
+
+    fn request_repaint(&self) {
+        if let Some(ctx) = &*self.ctx.read().unwrap_or_else(PoisonError::into_inner) {
+            ctx.request_repaint();
+        }
+    }
+}
+
+async fn stop_signal(stopped: async_channel::Receiver<()>) {
+    _ = stopped.recv().await;
+}
+
+async fn watch(shared: &Shared) {
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        shared.update(|s| s.status = WatcherStatus::Connecting);
+        match watch_connection(shared, &mut backoff).await {
+            // The portal went away, it's probably restarting.
+            Ok(()) => {}
+            Err(error) => shared.update(|s| {
+                s.status = match error {
+                    ashpd::Error::PortalNotFound(_) => WatcherStatus::Unavailable,
+                    error => WatcherStatus::Error(error.to_string()),
+                };
+                // Forget the last values, so that the sources after us are asked instead.
+                s.theme = None;
+                s.appearance = None;
+            }),
+        }
+        Timer::after(backoff).await;
+        backoff = (2 * backoff).min(MAX_BACKOFF);
+    }
+}
+
+enum Event {
+    SettingChanged,
+    PortalRestarted,
+}
+
+/// Watches the portal until its owner changes.
+async fn watch_connection(shared: &Shared, backoff: &mut Duration) -> ashpd::Result<()> {
+    let settings = Settings::new().await?;
+    // We subscribe before reading the initial values so that we don't miss any changes.
+    let owner_changes = settings
+        .receive_owner_changed()
+        .await
+        .map_err(ashpd::Error::from)?
+        .map(|_| Event::PortalRestarted);
+    let setting_changes = settings
+        .receive_setting_changed()
+        .await?
+        .filter(|s| {
+            s.namespace() == APPEARANCE_NAMESPACE
+                || (s.namespace() == GNOME_INTERFACE_NAMESPACE && s.key() == ENABLE_ANIMATIONS_KEY)
+        })
+        .map(|_| Event::SettingChanged);
+    let mut events = pin!(stream::or(owner_changes, setting_changes));
+
+    update(shared, &settings).await;
+    *backoff = INITIAL_BACKOFF;
+
+    while let Some(event) = events.next().await {
+        match event {
+            Event::PortalRestarted => return Ok(()),
+            Event::SettingChanged => {
+                if !debounce(&mut events).await {
+                    return Ok(());
+                }
+                update(shared, &settings).await;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Waits until no settings have changed for a while so that
+/// rapid back-and-forth changes only result in one update.
+/// Returns `false` if the portal went away in the meantime.
+async fn debounce(events: &mut (impl stream::Stream<Item = Event> + Unpin)) -> bool {
+    loop {
+        let next = async { Some(events.next().await) };
+        let timeout = async {
+            Timer::after(DEBOUNCE_INTERVAL).await;
+            None
+        };
+        match future::or(next, timeout).await {
+            Some(Some(Event::SettingChanged)) => continue,
+            Some(Some(Event::PortalRestarted) | None) => return false,
+            None => return true,
+        }
+    }
+}
+
+async fn update(shared: &Shared, settings: &Settings<'_>) {
+    // Not every portal implements every key, so missing keys are not an error.
+    let theme = settings.color_scheme().await.ok().and_then(to_theme);
+    let appearance = DesktopAppearance {
+        accent_color: settings.accent_color().await.ok().and_then(to_color32),
+        contrast: to_contrast(settings.contrast().await.ok()),
+        reduced_motion: settings
+            .read::<bool>(GNOME_INTERFACE_NAMESPACE, ENABLE_ANIMATIONS_KEY)
+            .await
+            .is_ok_and(|enabled| !enabled),
+    };
+    shared.update(|s| {
+        s.status = WatcherStatus::Active;
+        s.theme = theme;
+        s.appearance = Some(appearance);
+    });
+}
+
+fn to_theme(color_scheme: ColorScheme) -> Option<Theme> {
+    match color_scheme {
+        ColorScheme::NoPreference => None,
+        ColorScheme::PreferLight => Some(Theme::Light),
+        ColorScheme::PreferDark => Some(Theme::Dark),
+    }
+}
+
+fn to_color32(color: Color) -> Option<Color32> {
+    let [r, g, b] = [color.red(), color.green(), color.blue()];
+    Some(Color32::from_rgb(to_u8(r)?, to_u8(g)?, to_u8(b)?))
+}
+
+// The components are sRGB values in `0..=1`, the portal uses
+// values outside of that range to say that there's no accent colour.
+fn to_u8(component: f64) -> Option<u8> {
+    (0.0..=1.0)
+        .contains(&component)
+        .then(|| (component * 255.0).round() as u8)
+}
+
+fn to_contrast(contrast: Option<settings::Contrast>) -> Contrast {
+    match contrast {
+        Some(settings::Contrast::High) => Contrast::High,
+        Some(settings::Contrast::NoPreference) | None => Contrast::Normal,
+    }
+}
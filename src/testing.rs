@@ -0,0 +1,261 @@
+//! Helpers for testing apps that use the theme switch without a window.
+//!
+//! Every switch records where its options are, so tests can click them
+//! without knowing how the switch is laid out.
+//!
+//! ```
+//! use egui::{CentralPanel, ThemePreference};
+//! use egui_theme_switch::testing::Harness;
+//! use egui_theme_switch::ThemeSwitch;
+//!
+//! let mut preference = ThemePreference::System;
+//! let mut harness = Harness::new();
+//! let mut app = |ctx: &egui::Context| {
+//!     CentralPanel::default().show(ctx, |ui| {
+//!         ui.add(ThemeSwitch::new(&mut preference));
+//!     });
+//! };
+//!
+//! harness.run(&mut app);
+//! harness.click_option(ThemePreference::Dark);
+//! harness.run(&mut app);
+//! # drop(app);
+//! assert_eq!(preference, ThemePreference::Dark);
+//! ```
+
+use crate::{AllocatedSpace, SwitchOption};
+use egui::emath::{Pos2, Rect, Vec2};
+use egui::{
+    Context, Event, FullOutput, Id, Key, Modifiers, PointerButton, RawInput, Response, Theme,
+    ThemePreference,
+};
+
+/// A switch as it was laid out in the last pass.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchLayout<T> {
+    /// The id of the response returned by the switch.
+    pub id: Id,
+    pub rect: Rect,
+    /// Whether the switch is [collapsed](crate::ThemeSwitch::responsive) into a single button.
+    /// Its options are only laid out while the popup is open.
+    pub collapsed: bool,
+    pub options: Vec<OptionLayout<T>>,
+}
+
+/// One of the buttons of a switch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionLayout<T> {
+    pub value: T,
+    pub label: &'static str,
+    pub id: Id,
+    /// The area that reacts to clicks, this is larger than the visible button.
+    pub rect: Rect,
+    /// Additional information shown in the tooltip.
+    pub hint: Option<String>,
+}
+
+impl<T: PartialEq> SwitchLayout<T> {
+    pub fn option(&self, value: &T) -> Option<&OptionLayout<T>> {
+        self.options.iter().find(|option| &option.value == value)
+    }
+}
+
+/// The theme switches shown in the last pass, in the order they were shown.
+pub fn switches(ctx: &Context) -> Vec<SwitchLayout<ThemePreference>> {
+    let recorded: Option<Recorded<ThemePreference>> = ctx.data(|d| d.get_temp(recorded_id()));
+    recorded
+        // Passes that showed no switch don't record anything.
+        .filter(|recorded| recorded.pass + 1 >= ctx.cumulative_pass_nr())
+        .map(|recorded| recorded.switches)
+        .unwrap_or_default()
+}
+
+/// The theme switch with the given id, i.e. the id of the response returned by the switch.
+pub fn find_switch(ctx: &Context, id: Id) -> Option<SwitchLayout<ThemePreference>> {
+    switches(ctx).into_iter().find(|switch| switch.id == id)
+}
+
+#[derive(Debug, Clone)]
+struct Recorded<T> {
+    pass: u64,
+    switches: Vec<SwitchLayout<T>>,
+}
+
+fn recorded_id() -> Id {
+    Id::new("egui_theme_switch::testing::switches")
+}
+
+impl<T: Clone> SwitchLayout<T> {
+    pub(crate) fn from_space(space: &AllocatedSpace<T>) -> Self {
+        Self {
+            id: space.response.id,
+            rect: space.rect,
+            collapsed: false,
+            options: space
+                .buttons
+                .iter()
+                .map(|button| option_layout(&button.option, &button.response))
+                .collect(),
+        }
+    }
+
+    /// `rows` are the responses of the options in the popup, if it's open.
+    pub(crate) fn collapsed(
+        response: &Response,
+        options: &[SwitchOption<T>],
+        rows: &[Response],
+    ) -> Self {
+        Self {
+            id: response.id,
+            rect: response.rect,
+            collapsed: true,
+            options: options
+                .iter()
+                .zip(rows)
+                .map(|(option, row)| option_layout(option, row))
+                .collect(),
+        }
+    }
+}
+
+fn option_layout<T: Clone>(option: &SwitchOption<T>, response: &Response) -> OptionLayout<T> {
+    OptionLayout {
+        value: option.value.clone(),
+        label: option.label,
+        id: response.id,
+        rect: response.rect,
+        hint: option.hint.clone(),
+    }
+}
+
+pub(crate) fn record<T>(ctx: &Context, layout: SwitchLayout<T>)
+where
+    T: Clone + Send + Sync + 'static,
+{
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data_mut(|d| {
+        let recorded = d.get_temp_mut_or_insert_with(recorded_id(), || Recorded::<T> {
+            pass,
+            switches: Vec::new(),
+        });
+        if recorded.pass != pass {
+            recorded.pass = pass;
+            recorded.switches.clear();
+        }
+        recorded.switches.push(layout);
+    });
+}
+
+/// Drives a [`Context`] without a window: events and the system theme
+/// are queued up and passed to egui on the next [`run`](Harness::run).
+#[derive(Debug)]
+pub struct Harness {
+    ctx: Context,
+    screen_rect: Rect,
+    system_theme: Option<Theme>,
+    events: Vec<Event>,
+    time: f64,
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Harness {
+    /// A fresh context with an 800 × 600 screen and no system theme.
+    pub fn new() -> Self {
+        Self::with_context(Context::default())
+    }
+
+    pub fn with_context(ctx: Context) -> Self {
+        Self {
+            ctx,
+            screen_rect: Rect::from_min_size(Pos2::ZERO, Vec2::new(800.0, 600.0)),
+            system_theme: None,
+            events: Vec::new(),
+            time: 0.0,
+        }
+    }
+
+    pub fn ctx(&self) -> &Context {
+        &self.ctx
+    }
+
+    pub fn set_screen_size(&mut self, size: Vec2) {
+        self.screen_rect = Rect::from_min_size(Pos2::ZERO, size);
+    }
+
+    /// Pretends that the OS switched to `theme` (or stopped reporting one)
+    /// by setting `raw.system_theme` from the next pass on.
+    pub fn set_system_theme(&mut self, theme: Option<Theme>) {
+        self.system_theme = theme;
+    }
+
+    /// Runs one frame with all queued events, then advances the clock by one 60th of a second.
+    pub fn run(&mut self, run_ui: impl FnMut(&Context)) -> FullOutput {
+        let input = RawInput {
+            screen_rect: Some(self.screen_rect),
+            system_theme: self.system_theme,
+            time: Some(self.time),
+            predicted_dt: FRAME_TIME as f32,
+            events: std::mem::take(&mut self.events),
+            ..Default::default()
+        };
+        self.time += FRAME_TIME;
+        self.ctx.run(input, run_ui)
+    }
+
+    pub fn push_event(&mut self, event: Event) {
+        self.events.push(event);
+    }
+
+    /// Moves the pointer to `pos` and clicks the primary button.
+    ///
+    /// egui finds the clicked widget from the previous pass,
+    /// so the widget has to be shown before it can be clicked.
+    pub fn click(&mut self, pos: Pos2) {
+        self.hover(pos);
+        for pressed in [true, false] {
+            self.push_event(Event::PointerButton {
+                pos,
+                button: PointerButton::Primary,
+                pressed,
+                modifiers: Modifiers::NONE,
+            });
+        }
+    }
+
+    pub fn hover(&mut self, pos: Pos2) {
+        self.push_event(Event::PointerMoved(pos));
+    }
+
+    /// Presses and releases `key`.
+    pub fn press_key(&mut self, key: Key, modifiers: Modifiers) {
+        for pressed in [true, false] {
+            self.push_event(Event::Key {
+                key,
+                physical_key: None,
+                pressed,
+                repeat: false,
+                modifiers,
+            });
+        }
+    }
+
+    /// Clicks the option with `value` on the first switch that has one.
+    /// Collapsed switches only have options while their popup is open.
+    ///
+    /// # Panics
+    /// If no switch with the option was shown in the last pass.
+    pub fn click_option(&mut self, value: ThemePreference) {
+        let rect = switches(&self.ctx)
+            .iter()
+            .find_map(|switch| switch.option(&value).map(|option| option.rect))
+            .expect("a theme switch with the option was shown in the last pass");
+        self.click(rect.center());
+    }
+}
+
+const FRAME_TIME: f64 = 1.0 / 60.0;
@@ -0,0 +1,137 @@
+//! Observers that are told when the theme changes, whatever changed it.
+//!
+//! Changes are detected at the end of each pass by comparing the theme preference
+//! and the resolved theme with the previous pass, so every change is reported exactly once,
+//! even if the preference was set several times in between.
+//!
+//! ```
+//! use egui::{Theme, ThemePreference};
+//! use egui_theme_switch::theme_change::{self, Cause};
+//! use std::sync::{Arc, Mutex};
+//!
+//! let ctx = egui::Context::default();
+//! let changes = Arc::new(Mutex::new(Vec::new()));
+//! let sink = changes.clone();
+//! theme_change::observe(&ctx, move |_ctx, change| sink.lock().unwrap().push(*change));
+//!
+//! let _ = ctx.run(Default::default(), |_| {});
+//! let _ = ctx.run(Default::default(), |ctx| {
+//!     theme_change::set_theme(ctx, ThemePreference::Light, Cause::User);
+//! });
+//!
+//! let changes = changes.lock().unwrap();
+//! assert_eq!(changes.len(), 1);
+//! assert_eq!(changes[0].new_preference, ThemePreference::Light);
+//! assert_eq!(changes[0].theme, Theme::Light);
+//! assert_eq!(changes[0].cause, Cause::User);
+//! ```
+
+use egui::{Context, Id, Theme, ThemePreference};
+use std::fmt;
+use std::sync::Arc;
+
+/// A change of the theme preference, the resolved theme or both.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThemeChange {
+    pub old_preference: ThemePreference,
+    pub new_preference: ThemePreference,
+    /// The theme that is used from now on.
+    pub theme: Theme,
+    pub cause: Cause,
+}
+
+/// Why the theme changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cause {
+    /// The user picked a theme, e.g. with a [`GlobalThemeSwitch`](crate::GlobalThemeSwitch).
+    User,
+    /// The preference follows the system and the system theme changed.
+    System,
+    /// The app changed the preference, e.g. with [`Context::set_theme`].
+    Programmatic,
+}
+
+/// Calls `observer` at the end of every pass in which the theme changed.
+///
+/// The first pass after registering only records the current theme, so
+/// the system theme reported on startup isn't mistaken for a change.
+pub fn observe(ctx: &Context, observer: impl Fn(&Context, &ThemeChange) + Send + Sync + 'static) {
+    let first = ctx.data_mut(|d| {
+        let registry = d.get_temp_mut_or_default::<Registry>(registry_id());
+        registry.observers.push(Arc::new(observer));
+        registry.observers.len() == 1
+    });
+    if first {
+        ctx.on_end_pass("theme_change", Arc::new(end_pass));
+    }
+}
+
+/// Sets the theme preference like [`Context::set_theme`] and tells the observers why.
+///
+/// Changes made with [`Context::set_theme`] directly are reported as [`Cause::Programmatic`].
+/// Use this with [`Cause::User`] when the user changed the theme, e.g. with a hotkey.
+pub fn set_theme(ctx: &Context, preference: ThemePreference, cause: Cause) {
+    record_cause(ctx, cause);
+    ctx.set_theme(preference);
+}
+
+/// Remembers why the theme is about to change until the end of the pass.
+pub(crate) fn record_cause(ctx: &Context, cause: Cause) {
+    ctx.data_mut(|d| d.get_temp_mut_or_default::<Registry>(registry_id()).cause = Some(cause));
+}
+
+type Observer = Arc<dyn Fn(&Context, &ThemeChange) + Send + Sync>;
+
+#[derive(Clone, Default)]
+struct Registry {
+    observers: Vec<Observer>,
+    /// The preference and theme at the end of the previous pass.
+    last: Option<(ThemePreference, Theme)>,
+    cause: Option<Cause>,
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Registry")
+            .field("observers", &self.observers.len())
+            .field("last", &self.last)
+            .field("cause", &self.cause)
+            .finish()
+    }
+}
+
+fn end_pass(ctx: &Context) {
+    let current = (ctx.options(|opt| opt.theme_preference), ctx.theme());
+    let (last, cause, observers) = ctx.data_mut(|d| {
+        let registry = d.get_temp_mut_or_default::<Registry>(registry_id());
+        let last = registry.last.replace(current);
+        (last, registry.cause.take(), registry.observers.clone())
+    });
+    let Some((old_preference, old_theme)) = last else {
+        return;
+    };
+    if (old_preference, old_theme) == current {
+        return;
+    }
+
+    let (new_preference, theme) = current;
+    let cause = cause.unwrap_or(if old_preference == new_preference {
+        Cause::System
+    } else {
+        Cause::Programmatic
+    });
+    let change = ThemeChange {
+        old_preference,
+        new_preference,
+        theme,
+        cause,
+    };
+    // Called outside of `data_mut` so that observers can use the context.
+    for observer in observers {
+        observer(ctx, &change);
+    }
+}
+
+fn registry_id() -> Id {
+    Id::new("egui_theme_switch::theme_change::Registry")
+}
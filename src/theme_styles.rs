@@ -0,0 +1,118 @@
+use crate::{preview, transition};
+use egui::{Context, Id, Style, Theme};
+use std::sync::Arc;
+
+/// The styles used for the dark and the light theme, e.g. with your app's own palette.
+///
+/// Build them once and pass clones on: clones share their styles, so reinstalling
+/// them every pass is only a pointer comparison. Styles built anew each pass
+/// have to be compared field by field instead.
+///
+/// ```
+/// use egui::{Color32, Style, Theme};
+/// use egui_theme_switch::{GlobalThemeSwitch, ThemeStyles};
+///
+/// struct App {
+///     styles: ThemeStyles,
+/// }
+///
+/// impl App {
+///     fn new() -> Self {
+///         let styles = ThemeStyles::from_stock(|theme, style| {
+///             style.visuals.selection.bg_fill = match theme {
+///                 Theme::Dark => Color32::from_rgb(0x9d, 0x4e, 0xdd),
+///                 Theme::Light => Color32::from_rgb(0x5a, 0x18, 0x9a),
+///             };
+///         });
+///         Self { styles }
+///     }
+///
+///     fn ui(&self, ui: &mut egui::Ui) {
+///         ui.add(GlobalThemeSwitch::new().styles(self.styles.clone()));
+///     }
+/// }
+///
+/// let app = App::new();
+/// # egui::__run_test_ui(|ui| {
+/// app.ui(ui);
+/// # });
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThemeStyles {
+    pub dark: Arc<Style>,
+    pub light: Arc<Style>,
+}
+
+impl ThemeStyles {
+    pub fn new(dark: impl Into<Arc<Style>>, light: impl Into<Arc<Style>>) -> Self {
+        Self {
+            dark: dark.into(),
+            light: light.into(),
+        }
+    }
+
+    /// egui's default styles, modified by `tweak`.
+    pub fn from_stock(mut tweak: impl FnMut(Theme, &mut Style)) -> Self {
+        let [dark, light] = [Theme::Dark, Theme::Light].map(|theme| {
+            let mut style = theme.default_style();
+            tweak(theme, &mut style);
+            style
+        });
+        Self::new(dark, light)
+    }
+
+    pub fn style(&self, theme: Theme) -> &Arc<Style> {
+        match theme {
+            Theme::Dark => &self.dark,
+            Theme::Light => &self.light,
+        }
+    }
+
+    /// Installs both styles with [`Context::set_style_of`].
+    ///
+    /// This is cheap to call every pass with the same (or cloned) styles:
+    /// they're only installed when they differ from the ones installed last,
+    /// so changes made to the context's styles in the meantime
+    /// (e.g. by [`follow_accent_color`](crate::system_theme::follow_accent_color)
+    /// or a [transition](crate::ThemeTransition)) are kept.
+    /// Installing different styles ends a running cross-fade or preview.
+    pub fn install(&self, ctx: &Context) {
+        let installed = ctx.data(|d| d.get_temp::<Self>(installed_id()));
+        if let Some(installed) = installed {
+            if installed.shares_styles_with(self) {
+                return;
+            }
+            if same_style(&installed.dark, &self.dark) && same_style(&installed.light, &self.light)
+            {
+                // So the next pass only compares pointers if these are reused.
+                ctx.data_mut(|d| d.insert_temp(installed_id(), self.clone()));
+                return;
+            }
+        }
+        // Otherwise they'd restore the old styles when they end.
+        transition::finish_cross_fade(ctx);
+        preview::end(ctx);
+        for theme in [Theme::Dark, Theme::Light] {
+            ctx.set_style_of(theme, self.style(theme).clone());
+        }
+        ctx.data_mut(|d| d.insert_temp(installed_id(), self.clone()));
+        // Widgets shown earlier in this pass still use the old style.
+        ctx.request_discard("egui_theme_switch::ThemeStyles installed");
+    }
+
+    fn shares_styles_with(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.dark, &other.dark) && Arc::ptr_eq(&self.light, &other.light)
+    }
+}
+
+/// Styles that are created separately never compare equal because
+/// their number formatters are compared by pointer, so they're ignored.
+fn same_style(a: &Arc<Style>, b: &Arc<Style>) -> bool {
+    let mut a = Style::clone(a);
+    a.number_formatter = b.number_formatter.clone();
+    a == **b
+}
+
+fn installed_id() -> Id {
+    Id::new("egui_theme_switch::ThemeStyles")
+}
@@ -0,0 +1,155 @@
+use crate::transition;
+use egui::epaint::ClippedShape;
+use egui::layers::{PaintList, ShapeIdx};
+use egui::{Context, Id, Image, Rect, Response, Theme, Ui, Widget};
+use std::hash::Hash;
+
+/// An image with a dark and a light variant that shows whichever matches the current theme,
+/// so it follows along when the theme is changed, e.g. with a [`global_theme_switch`](crate::global_theme_switch).
+///
+/// Both variants accept anything that can be turned into an [`Image`],
+/// i.e. an [`ImageSource`](egui::ImageSource) or an already configured image.
+///
+/// ```
+/// use egui::{include_image, Image};
+/// use egui_theme_switch::ThemedImage;
+///
+/// # egui::__run_test_ui(|ui| {
+/// ui.add(
+///     ThemedImage::new(
+///         include_image!("../doc/dark.png"),
+///         include_image!("../doc/light.png"),
+///     )
+///     .map(|image| image.max_width(200.0))
+///     .cross_fade(0.3),
+/// );
+/// # });
+/// ```
+#[must_use = "You should put this widget in an ui with `ui.add(widget);`"]
+#[derive(Debug, Clone)]
+pub struct ThemedImage<'a> {
+    dark: Image<'a>,
+    light: Image<'a>,
+    cross_fade: Option<f32>,
+    id_salt: Option<Id>,
+}
+
+impl<'a> ThemedImage<'a> {
+    pub fn new(dark: impl Into<Image<'a>>, light: impl Into<Image<'a>>) -> Self {
+        Self {
+            dark: dark.into(),
+            light: light.into(),
+            cross_fade: None,
+            id_salt: None,
+        }
+    }
+
+    /// Applies the same options to both variants, e.g. `|image| image.max_width(64.0)`.
+    pub fn map(self, mut f: impl FnMut(Image<'a>) -> Image<'a>) -> Self {
+        Self {
+            dark: f(self.dark),
+            light: f(self.light),
+            ..self
+        }
+    }
+
+    /// Fades from one variant to the other over `duration` seconds when the theme changes.
+    ///
+    /// Like [transitions](crate::ThemeTransition), the fade is skipped
+    /// when animations are disabled or the desktop asks for reduced motion.
+    pub fn cross_fade(mut self, duration: f32) -> Self {
+        self.cross_fade = Some(duration);
+        self
+    }
+
+    /// The cross-fade is tracked by the image's id, which is generated from its position
+    /// in the layout by default. Use a salt that is unique within the parent ui to keep it stable.
+    pub fn id_salt(mut self, id_salt: impl Hash) -> Self {
+        self.id_salt = Some(Id::new(id_salt));
+        self
+    }
+
+    pub fn image(&self, theme: Theme) -> &Image<'a> {
+        match theme {
+            Theme::Dark => &self.dark,
+            Theme::Light => &self.light,
+        }
+    }
+
+    /// The variant for the current theme,
+    /// to be used with `ui.add` or [`Image::paint_at`] like any other image.
+    pub fn current(&self, ctx: &Context) -> &Image<'a> {
+        self.image(ctx.theme())
+    }
+
+    fn into_image(self, theme: Theme) -> Image<'a> {
+        match theme {
+            Theme::Dark => self.dark,
+            Theme::Light => self.light,
+        }
+    }
+}
+
+impl Widget for ThemedImage<'_> {
+    fn ui(self, ui: &mut Ui) -> Response {
+        let theme = ui.ctx().theme();
+        let duration = match self.cross_fade {
+            Some(duration) if !transition::reduced_motion(ui.ctx()) => duration,
+            _ => return ui.add(self.into_image(theme)),
+        };
+
+        let id = match self.id_salt {
+            Some(salt) => ui.make_persistent_id(salt),
+            None => ui.next_auto_id(),
+        };
+        let darkness = ui
+            .ctx()
+            .animate_bool_with_time(id, theme == Theme::Dark, duration);
+        let (current, previous, opacity) = match theme {
+            Theme::Dark => (self.dark, self.light, darkness),
+            Theme::Light => (self.light, self.dark, 1.0 - darkness),
+        };
+
+        // The current variant decides the layout and fades in on top of the previous one,
+        // which stays opaque so that the background doesn't shine through halfway.
+        let first = next_shape_idx(ui);
+        let response = ui.add(fade(current, opacity));
+        if opacity < 1.0 && ui.is_rect_visible(response.rect) {
+            paint_underneath(ui, first, &previous, response.rect);
+        }
+        response
+    }
+}
+
+/// Paints `image` underneath the shapes that were painted since `first`.
+fn paint_underneath(ui: &Ui, first: ShapeIdx, image: &Image<'_>, rect: Rect) {
+    let split = next_shape_idx(ui);
+    image.paint_at(ui, rect);
+    let end = next_shape_idx(ui);
+    ui.ctx().graphics_mut(|graphics| {
+        let list = graphics.entry(ui.layer_id());
+        let mut shapes: Vec<ClippedShape> = list
+            .all_entries()
+            .skip(first.0)
+            .take(end.0 - first.0)
+            .cloned()
+            .collect();
+        shapes.rotate_right(end.0 - split.0);
+        for (idx, ClippedShape { clip_rect, shape }) in (first.0..).zip(shapes) {
+            list.set(ShapeIdx(idx), clip_rect, shape);
+        }
+    });
+}
+
+fn next_shape_idx(ui: &Ui) -> ShapeIdx {
+    ui.ctx().graphics(|graphics| {
+        graphics
+            .get(ui.layer_id())
+            .map_or(ShapeIdx(0), PaintList::next_idx)
+    })
+}
+
+fn fade(image: Image<'_>, opacity: f32) -> Image<'_> {
+    let tint = image.image_options().tint.gamma_multiply(opacity);
+    image.tint(tint)
+}
@@ -0,0 +1,304 @@
+use crate::system_theme::DesktopAppearance;
+use egui::emath::{lerp, Pos2, Rect, Vec2};
+use egui::epaint::{Color32, Mesh, Shadow, Stroke};
+use egui::style::{Visuals, WidgetVisuals};
+use egui::{Context, Id, LayerId, Order, Theme, ThemePreference};
+use std::f32::consts::TAU;
+use std::sync::Arc;
+
+/// An animated transition that is played when the theme
+/// is changed using a [`GlobalThemeSwitch`](crate::GlobalThemeSwitch).
+///
+/// Transitions are skipped when animations are disabled (i.e. the style's `animation_time` is zero)
+/// or when the desktop asks for [reduced motion](DesktopAppearance::reduced_motion).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ThemeTransition {
+    /// Fades the colours of the old theme into the colours
+    /// of the new theme over `duration` seconds.
+    CrossFade { duration: f32 },
+    /// Reveals the new theme in a circle that grows from the clicked
+    /// button until it covers the screen, over `duration` seconds.
+    ///
+    /// This is a wipe rather than a true reveal: the old frame isn't kept around,
+    /// so until the circle covers the screen, everything outside of it
+    /// (widgets included) is hidden behind the old theme's background.
+    CircularReveal { duration: f32 },
+}
+
+impl ThemeTransition {
+    pub fn cross_fade(duration: f32) -> Self {
+        Self::CrossFade { duration }
+    }
+
+    pub fn circular_reveal(duration: f32) -> Self {
+        Self::CircularReveal { duration }
+    }
+}
+
+/// Changes the theme preference and starts the transition from the current theme.
+/// `origin` is the center of the button that was clicked.
+pub(crate) fn set_theme(
+    ctx: &Context,
+    preference: ThemePreference,
+    transition: ThemeTransition,
+    origin: Pos2,
+) {
+    let from = ctx.style().visuals.clone();
+    // A cross-fade that's still running is finished first so that the
+    // old theme's visuals are restored before we switch away from them.
+    finish_cross_fade(ctx);
+    ctx.set_theme(preference);
+    if reduced_motion(ctx) {
+        return;
+    }
+    register(ctx);
+    match transition {
+        ThemeTransition::CrossFade { duration } => start_cross_fade(ctx, from, duration),
+        ThemeTransition::CircularReveal { duration } => {
+            start_reveal(ctx, from.panel_fill, origin, duration)
+        }
+    }
+}
+
+pub(crate) fn reduced_motion(ctx: &Context) -> bool {
+    ctx.style().animation_time <= 0.0 || DesktopAppearance::load(ctx).reduced_motion
+}
+
+fn register(ctx: &Context) {
+    if !ctx.data(|d| d.get_temp::<bool>(plugin_id()).unwrap_or_default()) {
+        ctx.on_begin_pass("theme_transition", Arc::new(update));
+        ctx.data_mut(|d| d.insert_temp(plugin_id(), true));
+    }
+}
+
+fn update(ctx: &Context) {
+    update_cross_fade(ctx);
+    update_reveal(ctx);
+}
+
+// Each transition gets its own animation so that it always starts from zero.
+fn next_animation_id(ctx: &Context) -> Id {
+    ctx.data_mut(|d| {
+        let generation = d.get_temp_mut_or_default::<u64>(generation_id());
+        *generation += 1;
+        generation_id().with(*generation)
+    })
+}
+
+#[derive(Clone)]
+struct CrossFade {
+    from: Visuals,
+    to: Visuals,
+    theme: Theme,
+    duration: f32,
+    animation_id: Id,
+}
+
+fn start_cross_fade(ctx: &Context, from: Visuals, duration: f32) {
+    let theme = ctx.theme();
+    let cross_fade = CrossFade {
+        to: ctx.style_of(theme).visuals.clone(),
+        from,
+        theme,
+        duration,
+        animation_id: next_animation_id(ctx),
+    };
+    // We apply the first step right away, otherwise the new theme flashes for one pass.
+    ctx.animate_value_with_time(cross_fade.animation_id, 0.0, duration);
+    ctx.style_mut_of(theme, |style| style.visuals = cross_fade.from.clone());
+    ctx.data_mut(|d| d.insert_temp(cross_fade_id(), cross_fade));
+}
+
+fn update_cross_fade(ctx: &Context) {
+    let Some(cross_fade) = ctx.data(|d| d.get_temp::<CrossFade>(cross_fade_id())) else {
+        return;
+    };
+    let t = ctx.animate_value_with_time(cross_fade.animation_id, 1.0, cross_fade.duration);
+    // The theme might also change by other means (e.g. the system theme changes).
+    if t >= 1.0 || ctx.theme() != cross_fade.theme {
+        finish_cross_fade(ctx);
+    } else {
+        let visuals = lerp_visuals(&cross_fade.from, &cross_fade.to, ease(t));
+        ctx.style_mut_of(cross_fade.theme, |style| style.visuals = visuals);
+    }
+}
+
+/// Restores the real visuals of the target theme.
+pub(crate) fn finish_cross_fade(ctx: &Context) {
+    let cross_fade = ctx.data_mut(|d| {
+        let cross_fade = d.get_temp::<CrossFade>(cross_fade_id());
+        d.remove::<CrossFade>(cross_fade_id());
+        cross_fade
+    });
+    if let Some(CrossFade { to, theme, .. }) = cross_fade {
+        ctx.style_mut_of(theme, |style| style.visuals = to);
+    }
+}
+
+fn cross_fade_id() -> Id {
+    Id::new("egui_theme_switch::CrossFade")
+}
+
+#[derive(Clone)]
+struct Reveal {
+    origin: Pos2,
+    background: Color32,
+    duration: f32,
+    animation_id: Id,
+}
+
+fn start_reveal(ctx: &Context, background: Color32, origin: Pos2, duration: f32) {
+    let reveal = Reveal {
+        origin,
+        background,
+        duration,
+        animation_id: next_animation_id(ctx),
+    };
+    ctx.animate_value_with_time(reveal.animation_id, 0.0, duration);
+    paint_reveal(ctx, &reveal, 0.0);
+    ctx.data_mut(|d| d.insert_temp(reveal_id(), reveal));
+}
+
+fn update_reveal(ctx: &Context) {
+    let Some(reveal) = ctx.data(|d| d.get_temp::<Reveal>(reveal_id())) else {
+        return;
+    };
+    let t = ctx.animate_value_with_time(reveal.animation_id, 1.0, reveal.duration);
+    if t >= 1.0 {
+        ctx.data_mut(|d| d.remove::<Reveal>(reveal_id()));
+    } else {
+        paint_reveal(ctx, &reveal, ease(t));
+    }
+}
+
+// The old background covers everything outside of the growing circle, widgets included.
+fn paint_reveal(ctx: &Context, reveal: &Reveal, t: f32) {
+    let screen = ctx.screen_rect();
+    let max_radius = farthest_corner_distance(screen, reveal.origin);
+    let radius = t * max_radius;
+    let feathering = 1.0 / ctx.pixels_per_point();
+    let mesh = ring_mesh(
+        reveal.origin,
+        radius,
+        max_radius + feathering,
+        feathering,
+        reveal.background,
+    );
+    ctx.layer_painter(LayerId::new(Order::Foreground, reveal_id()))
+        .with_clip_rect(screen)
+        .add(mesh);
+}
+
+fn farthest_corner_distance(rect: Rect, pos: Pos2) -> f32 {
+    [
+        rect.left_top(),
+        rect.right_top(),
+        rect.left_bottom(),
+        rect.right_bottom(),
+    ]
+    .into_iter()
+    .map(|corner| corner.distance(pos))
+    .fold(0.0, f32::max)
+}
+
+/// A ring between `inner_radius` and `outer_radius`
+/// with an anti-aliased inner edge.
+fn ring_mesh(
+    center: Pos2,
+    inner_radius: f32,
+    outer_radius: f32,
+    feathering: f32,
+    color: Color32,
+) -> Mesh {
+    let segments = (outer_radius / 8.0).ceil().clamp(32.0, 256.0) as u32;
+    let radii = [
+        (inner_radius, Color32::TRANSPARENT),
+        (inner_radius + feathering, color),
+        (outer_radius, color),
+    ];
+    let mut mesh = Mesh::default();
+    for n in 0..segments {
+        let direction = Vec2::angled(TAU * n as f32 / segments as f32);
+        for (radius, color) in radii {
+            mesh.colored_vertex(center + radius * direction, color);
+        }
+    }
+    let rings = radii.len() as u32;
+    for n in 0..segments {
+        let current = n * rings;
+        let next = ((n + 1) % segments) * rings;
+        for ring in 0..rings - 1 {
+            mesh.add_triangle(current + ring, next + ring, current + ring + 1);
+            mesh.add_triangle(next + ring, next + ring + 1, current + ring + 1);
+        }
+    }
+    mesh
+}
+
+fn reveal_id() -> Id {
+    Id::new("egui_theme_switch::Reveal")
+}
+
+fn generation_id() -> Id {
+    Id::new("egui_theme_switch::ThemeTransition::generation")
+}
+
+fn plugin_id() -> Id {
+    Id::new("egui_theme_switch::ThemeTransition")
+}
+
+fn ease(t: f32) -> f32 {
+    // Smoothstep
+    t * t * (3.0 - 2.0 * t)
+}
+
+// Only the colours are interpolated, everything else is taken from the target.
+fn lerp_visuals(from: &Visuals, to: &Visuals, t: f32) -> Visuals {
+    let color = |a: Color32, b: Color32| a.lerp_to_gamma(b, t);
+    let stroke = |a: Stroke, b: Stroke| lerp_stroke(a, b, t);
+    let shadow = |a: Shadow, b: Shadow| Shadow {
+        color: color(a.color, b.color),
+        ..b
+    };
+    let widget = |a: &WidgetVisuals, b: &WidgetVisuals| WidgetVisuals {
+        bg_fill: color(a.bg_fill, b.bg_fill),
+        weak_bg_fill: color(a.weak_bg_fill, b.weak_bg_fill),
+        bg_stroke: stroke(a.bg_stroke, b.bg_stroke),
+        fg_stroke: stroke(a.fg_stroke, b.fg_stroke),
+        ..*b
+    };
+
+    let mut visuals = to.clone();
+    visuals.override_text_color = match (from.override_text_color, to.override_text_color) {
+        (Some(a), Some(b)) => Some(color(a, b)),
+        (_, b) => b,
+    };
+    visuals.widgets.noninteractive =
+        widget(&from.widgets.noninteractive, &to.widgets.noninteractive);
+    visuals.widgets.inactive = widget(&from.widgets.inactive, &to.widgets.inactive);
+    visuals.widgets.hovered = widget(&from.widgets.hovered, &to.widgets.hovered);
+    visuals.widgets.active = widget(&from.widgets.active, &to.widgets.active);
+    visuals.widgets.open = widget(&from.widgets.open, &to.widgets.open);
+    visuals.selection.bg_fill = color(from.selection.bg_fill, to.selection.bg_fill);
+    visuals.selection.stroke = stroke(from.selection.stroke, to.selection.stroke);
+    visuals.hyperlink_color = color(from.hyperlink_color, to.hyperlink_color);
+    visuals.faint_bg_color = color(from.faint_bg_color, to.faint_bg_color);
+    visuals.extreme_bg_color = color(from.extreme_bg_color, to.extreme_bg_color);
+    visuals.code_bg_color = color(from.code_bg_color, to.code_bg_color);
+    visuals.warn_fg_color = color(from.warn_fg_color, to.warn_fg_color);
+    visuals.error_fg_color = color(from.error_fg_color, to.error_fg_color);
+    visuals.window_shadow = shadow(from.window_shadow, to.window_shadow);
+    visuals.window_fill = color(from.window_fill, to.window_fill);
+    visuals.window_stroke = stroke(from.window_stroke, to.window_stroke);
+    visuals.panel_fill = color(from.panel_fill, to.panel_fill);
+    visuals.popup_shadow = shadow(from.popup_shadow, to.popup_shadow);
+    visuals.text_cursor.stroke = stroke(from.text_cursor.stroke, to.text_cursor.stroke);
+    visuals
+}
+
+fn lerp_stroke(from: Stroke, to: Stroke, t: f32) -> Stroke {
+    Stroke::new(
+        lerp(from.width..=to.width, t),
+        from.color.lerp_to_gamma(to.color, t),
+    )
+}
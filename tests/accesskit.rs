@@ -0,0 +1,198 @@
+#![cfg(feature = "accesskit")]
+
+use egui::accesskit::{Action, ActionRequest, Node, NodeId, Role, Toggled, TreeUpdate};
+use egui::{
+    Align, CentralPanel, Context, Event, Key, Layout, Modifiers, RawInput, ThemePreference,
+};
+use egui_theme_switch::{LabelPosition, ThemeSwitch};
+
+#[test]
+fn switch_is_a_radio_group_with_positions() {
+    let mut harness = Harness::new(ThemePreference::Dark);
+    let tree = harness.run(Vec::new());
+
+    let (_, group) = tree.find(Role::RadioGroup, "Theme");
+    let buttons = group.children();
+    assert_eq!(buttons.len(), 3);
+
+    let expected = [("Follow System", false), ("Dark", true), ("Light", false)];
+    for (n, (&id, (label, selected))) in buttons.iter().zip(expected).enumerate() {
+        let button = tree.node(id);
+        assert_eq!(button.role(), Role::RadioButton);
+        assert_eq!(button.label(), Some(label));
+        assert_eq!(button.position_in_set(), Some(n + 1));
+        assert_eq!(button.size_of_set(), Some(3));
+        assert_eq!(button.is_selected(), Some(selected));
+        let toggled = if selected {
+            Toggled::True
+        } else {
+            Toggled::False
+        };
+        assert_eq!(button.toggled(), Some(toggled));
+        // Only the selected button is a tab stop.
+        assert_eq!(button.supports_action(Action::Focus), selected);
+        assert!(button.supports_action(Action::Click));
+    }
+}
+
+#[test]
+fn click_action_selects_the_button() {
+    let mut harness = Harness::new(ThemePreference::Dark);
+    let tree = harness.run(Vec::new());
+    let (light, _) = tree.find(Role::RadioButton, "Light");
+
+    harness.run(vec![action(Action::Click, light)]);
+    assert_eq!(harness.value, ThemePreference::Light);
+
+    let tree = harness.run(Vec::new());
+    let (_, light) = tree.find(Role::RadioButton, "Light");
+    assert_eq!(light.toggled(), Some(Toggled::True));
+    assert!(light.supports_action(Action::Focus));
+}
+
+#[test]
+fn arrow_keys_move_the_selection() {
+    let mut harness = Harness::new(ThemePreference::Dark);
+    let tree = harness.run(Vec::new());
+    let (dark, _) = tree.find(Role::RadioButton, "Dark");
+
+    harness.run(vec![action(Action::Focus, dark)]);
+    let tree = harness.run(Vec::new());
+    assert_eq!(tree.focus, dark);
+
+    harness.run(vec![key(Key::ArrowRight)]);
+    assert_eq!(harness.value, ThemePreference::Light);
+    let tree = harness.run(Vec::new());
+    let (light, _) = tree.find(Role::RadioButton, "Light");
+    assert_eq!(tree.focus, light);
+
+    // The selection wraps around.
+    harness.run(vec![key(Key::ArrowRight)]);
+    assert_eq!(harness.value, ThemePreference::System);
+}
+
+#[test]
+fn arrow_keys_follow_the_visual_order_in_right_to_left_layouts() {
+    let mut harness = Harness::new(ThemePreference::Dark);
+    harness.layout = Layout::right_to_left(Align::Center);
+    let tree = harness.run(Vec::new());
+    let (dark, _) = tree.find(Role::RadioButton, "Dark");
+    harness.run(vec![action(Action::Focus, dark)]);
+
+    // "Light" is to the left of "Dark".
+    harness.run(vec![key(Key::ArrowLeft)]);
+    assert_eq!(harness.value, ThemePreference::Light);
+    harness.run(vec![key(Key::ArrowRight)]);
+    assert_eq!(harness.value, ThemePreference::Dark);
+}
+
+#[test]
+fn visible_label_names_the_group() {
+    let mut harness = Harness::new(ThemePreference::Dark);
+    harness.configure = |switch| switch.label("Appearance");
+    let tree = harness.run(Vec::new());
+
+    let (_, group) = tree.find(Role::RadioGroup, "Appearance");
+    let (caption, _) = tree
+        .nodes
+        .iter()
+        .find(|(_, node)| node.role() == Role::Label && node.value() == Some("Appearance"))
+        .expect("caption exists");
+    assert_eq!(group.labelled_by(), [*caption]);
+
+    // Clicking the caption focuses the selected button.
+    harness.run(vec![action(Action::Click, *caption)]);
+    let tree = harness.run(Vec::new());
+    let (dark, _) = tree.find(Role::RadioButton, "Dark");
+    assert_eq!(tree.focus, dark);
+}
+
+#[test]
+fn accessible_name_overrides_the_label() {
+    let mut harness = Harness::new(ThemePreference::Dark);
+    harness.configure = |switch| {
+        switch
+            .label("Appearance")
+            .label_position(LabelPosition::Right)
+            .accessible_name("Colour scheme")
+    };
+    let tree = harness.run(Vec::new());
+    tree.find(Role::RadioGroup, "Colour scheme");
+}
+
+struct Harness {
+    ctx: Context,
+    value: ThemePreference,
+    layout: Layout,
+    configure: fn(ThemeSwitch<'_>) -> ThemeSwitch<'_>,
+}
+
+impl Harness {
+    fn new(value: ThemePreference) -> Self {
+        let ctx = Context::default();
+        ctx.enable_accesskit();
+        Self {
+            ctx,
+            value,
+            layout: Layout::default(),
+            configure: |switch| switch,
+        }
+    }
+
+    fn run(&mut self, events: Vec<Event>) -> TreeUpdate {
+        let input = RawInput {
+            events,
+            ..Default::default()
+        };
+        let output = self.ctx.run(input, |ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                let switch = (self.configure)(ThemeSwitch::new(&mut self.value));
+                ui.with_layout(self.layout, |ui| ui.add(switch));
+            });
+        });
+        output
+            .platform_output
+            .accesskit_update
+            .expect("accesskit is enabled")
+    }
+}
+
+trait TreeUpdateExt {
+    fn node(&self, id: NodeId) -> &Node;
+    fn find(&self, role: Role, label: &str) -> (NodeId, &Node);
+}
+
+impl TreeUpdateExt for TreeUpdate {
+    fn node(&self, id: NodeId) -> &Node {
+        self.nodes
+            .iter()
+            .find_map(|(i, node)| (*i == id).then_some(node))
+            .expect("node exists")
+    }
+
+    fn find(&self, role: Role, label: &str) -> (NodeId, &Node) {
+        self.nodes
+            .iter()
+            .find(|(_, node)| node.role() == role && node.label() == Some(label))
+            .map(|(id, node)| (*id, node))
+            .expect("node exists")
+    }
+}
+
+fn action(action: Action, target: NodeId) -> Event {
+    Event::AccessKitActionRequest(ActionRequest {
+        action,
+        target,
+        data: None,
+    })
+}
+
+fn key(key: Key) -> Event {
+    Event::Key {
+        key,
+        physical_key: None,
+        pressed: true,
+        repeat: false,
+        modifiers: Modifiers::NONE,
+    }
+}
@@ -0,0 +1,137 @@
+use egui::emath::{pos2, vec2, Pos2, Vec2};
+use egui::epaint::Color32;
+use egui_theme_switch::arc::{bezier_error, AnnularSector, EllipticalArc, PieSlice};
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+const SAMPLES: usize = 200;
+
+#[test]
+fn bezier_error_matches_sampled_error() {
+    for sweep in [0.1, 0.5, 1.0, FRAC_PI_2] {
+        let arc = EllipticalArc::circle(Pos2::ZERO, 1.0, 0.0..=sweep).with_max_error(1.0);
+        let [bezier] = arc.to_cubic_beziers()[..] else {
+            panic!("expected a single bézier for {sweep}");
+        };
+        let sampled = max_radial_error(bezier, Pos2::ZERO, 1.0);
+        let bound = bezier_error(sweep);
+        assert!(sampled <= bound * 1.01 + 1e-6, "{sampled} > {bound}");
+        assert!(
+            sampled >= bound * 0.9 - 1e-6,
+            "{sampled} is not close to {bound}"
+        );
+    }
+}
+
+#[test]
+fn circular_beziers_stay_within_max_error() {
+    let center = pos2(10.0, -5.0);
+    for radius in [1.0, 10.0, 100.0, 1000.0] {
+        for max_error in [0.5, 0.1, 0.01] {
+            for angles in [0.0..=FRAC_PI_2, 0.3..=TAU, PI..=-PI] {
+                let arc = EllipticalArc::circle(center, radius, angles).with_max_error(max_error);
+                for bezier in arc.to_cubic_beziers() {
+                    let error = max_radial_error(bezier, center, radius);
+                    assert!(error <= max_error * 1.01, "{error} > {max_error}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn smaller_max_error_subdivides_more() {
+    let arc = EllipticalArc::circle(Pos2::ZERO, 500.0, 0.0..=TAU);
+    let coarse = arc.with_max_error(1.0).to_cubic_beziers().len();
+    let fine = arc.with_max_error(0.001).to_cubic_beziers().len();
+    assert_eq!(coarse, 4);
+    assert!(fine > coarse);
+}
+
+#[test]
+fn beziers_are_connected() {
+    let arc = EllipticalArc::ellipse(Pos2::ZERO, vec2(40.0, 10.0), 0.0..=5.0).with_max_error(0.01);
+    let beziers = arc.to_cubic_beziers();
+    assert_eq!(beziers[0][0], arc.point_at(0.0));
+    assert!(beziers[beziers.len() - 1][3].distance(arc.point_at(5.0)) < 1e-4);
+    for pair in beziers.windows(2) {
+        assert_eq!(pair[0][3], pair[1][0]);
+    }
+}
+
+#[test]
+fn elliptical_beziers_stay_within_max_error() {
+    let radius = vec2(100.0, 20.0);
+    let max_error = 0.05;
+    let arc = EllipticalArc::ellipse(Pos2::ZERO, radius, 0.0..=TAU).with_max_error(max_error);
+    for bezier in arc.to_cubic_beziers() {
+        // Scaling back to the unit circle shrinks the error by at most the larger radius.
+        let unit = bezier.map(|p| Pos2::ZERO + (p.to_vec2() / radius));
+        let error = max_radial_error(unit, Pos2::ZERO, 1.0);
+        assert!(error * radius.max_elem() <= max_error * 1.01);
+    }
+}
+
+#[test]
+fn flattened_points_stay_within_max_error() {
+    let center = pos2(3.0, 4.0);
+    for radius in [2.0, 50.0, 800.0] {
+        let max_error = 0.1;
+        let arc = EllipticalArc::circle(center, radius, 0.0..=3.0).with_max_error(max_error);
+        let points = arc.points();
+        for line in points.windows(2) {
+            // The sagitta is the largest distance between the chord and the arc.
+            let midpoint = line[0] + 0.5 * (line[1] - line[0]);
+            let sagitta = radius - midpoint.distance(center);
+            assert!(sagitta <= max_error * 1.01, "{sagitta} > {max_error}");
+        }
+    }
+}
+
+#[test]
+fn pie_slice_mesh_covers_the_slice() {
+    let arc = EllipticalArc::circle(Pos2::ZERO, 10.0, 0.0..=1.5 * PI).with_max_error(0.01);
+    let mesh = PieSlice::new(arc).to_mesh(Color32::RED);
+    assert!(mesh.is_valid());
+    let area: f32 = triangle_areas(&mesh).sum();
+    let expected = 0.5 * 1.5 * PI * 100.0;
+    assert!(
+        (area - expected).abs() / expected < 0.01,
+        "{area} != {expected}"
+    );
+}
+
+#[test]
+fn annular_sector_mesh_covers_the_sector() {
+    let sector = AnnularSector::new(Pos2::ZERO, 5.0..=10.0, 0.0..=PI).with_max_error(0.01);
+    let mesh = sector.to_mesh(Color32::RED);
+    assert!(mesh.is_valid());
+    let area: f32 = triangle_areas(&mesh).sum();
+    let expected = 0.5 * PI * (100.0 - 25.0);
+    assert!(
+        (area - expected).abs() / expected < 0.01,
+        "{area} != {expected}"
+    );
+}
+
+fn max_radial_error(bezier: [Pos2; 4], center: Pos2, radius: f32) -> f32 {
+    (0..=SAMPLES)
+        .map(|n| evaluate(bezier, n as f32 / SAMPLES as f32))
+        .map(|p| (p.distance(center) - radius).abs())
+        .fold(0.0, f32::max)
+}
+
+fn evaluate([p0, p1, p2, p3]: [Pos2; 4], t: f32) -> Pos2 {
+    let s = 1.0 - t;
+    let v: Vec2 = s * s * s * p0.to_vec2()
+        + 3.0 * s * s * t * p1.to_vec2()
+        + 3.0 * s * t * t * p2.to_vec2()
+        + t * t * t * p3.to_vec2();
+    v.to_pos2()
+}
+
+fn triangle_areas(mesh: &egui::Mesh) -> impl Iterator<Item = f32> + '_ {
+    mesh.triangles().map(|[a, b, c]| {
+        let [a, b, c] = [a, b, c].map(|i| mesh.vertices[i as usize].pos);
+        0.5 * ((b - a).x * (c - a).y - (b - a).y * (c - a).x).abs()
+    })
+}
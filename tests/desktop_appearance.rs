@@ -0,0 +1,110 @@
+#![cfg(feature = "testing")]
+
+use egui::epaint::{CircleShape, ClippedShape, Shape, Stroke};
+use egui::style::Selection;
+use egui::{CentralPanel, Color32, Context, FullOutput, Theme, ThemePreference};
+use egui_theme_switch::system_theme::{self, Contrast, DesktopAppearance};
+use egui_theme_switch::testing::Harness;
+use egui_theme_switch::ThemeSwitch;
+
+const ACCENT: Color32 = Color32::from_rgb(0xe6, 0x61, 0x00);
+
+#[test]
+fn selection_follows_the_accent_color() {
+    let mut harness = Harness::new();
+    let original = selections(harness.ctx());
+    system_theme::follow_accent_color(harness.ctx());
+
+    set_accent_color(&harness, Some(ACCENT));
+    harness.run(show);
+    for selection in selections(harness.ctx()) {
+        assert_eq!(selection.bg_fill, ACCENT);
+        assert_eq!(selection.stroke.color, Color32::BLACK);
+    }
+
+    set_accent_color(&harness, None);
+    harness.run(show);
+    assert_eq!(selections(harness.ctx()), original);
+}
+
+#[test]
+fn changing_the_accent_color_keeps_the_original_selection() {
+    let mut harness = Harness::new();
+    let original = selections(harness.ctx());
+    system_theme::follow_accent_color(harness.ctx());
+
+    set_accent_color(&harness, Some(ACCENT));
+    harness.run(show);
+    set_accent_color(&harness, Some(Color32::from_rgb(0x1c, 0x71, 0xd8)));
+    harness.run(show);
+    set_accent_color(&harness, None);
+    harness.run(show);
+    assert_eq!(selections(harness.ctx()), original);
+}
+
+#[test]
+fn selection_set_by_the_app_is_not_overwritten_on_restore() {
+    let mut harness = Harness::new();
+    system_theme::follow_accent_color(harness.ctx());
+    set_accent_color(&harness, Some(ACCENT));
+    harness.run(show);
+
+    harness.ctx().style_mut_of(Theme::Dark, |style| {
+        style.visuals.selection.bg_fill = Color32::RED;
+    });
+    set_accent_color(&harness, None);
+    harness.run(show);
+    let selection = harness.ctx().style_of(Theme::Dark).visuals.selection;
+    assert_eq!(selection.bg_fill, Color32::RED);
+}
+
+#[test]
+fn high_contrast_strengthens_the_indicator_stroke() {
+    let mut harness = Harness::new();
+    harness.ctx().set_theme(ThemePreference::Dark);
+    let output = harness.run(show);
+    assert_eq!(indicator(&harness, &output).stroke, Stroke::NONE);
+
+    DesktopAppearance {
+        contrast: Contrast::High,
+        ..Default::default()
+    }
+    .store(harness.ctx());
+    let output = harness.run(show);
+    let selection = harness.ctx().style().visuals.selection;
+    assert_eq!(
+        indicator(&harness, &output).stroke,
+        Stroke::new(2.0, selection.stroke.color)
+    );
+}
+
+fn set_accent_color(harness: &Harness, accent_color: Option<Color32>) {
+    DesktopAppearance {
+        accent_color,
+        ..Default::default()
+    }
+    .store(harness.ctx());
+}
+
+fn selections(ctx: &Context) -> [Selection; 2] {
+    [Theme::Dark, Theme::Light].map(|theme| ctx.style_of(theme).visuals.selection)
+}
+
+fn show(ctx: &Context) {
+    CentralPanel::default().show(ctx, |ui| {
+        ui.add(ThemeSwitch::new(&mut ThemePreference::Dark));
+    });
+}
+
+/// The circle filled with the selection colour behind the selected option.
+fn indicator(harness: &Harness, output: &FullOutput) -> CircleShape {
+    let fill = harness.ctx().style().visuals.selection.bg_fill;
+    output
+        .shapes
+        .iter()
+        .find_map(|ClippedShape { shape, .. }| match shape {
+            Shape::Circle(circle) if circle.fill == fill => Some(*circle),
+            _ => None,
+        })
+        .expect("active indicator is painted")
+}
@@ -0,0 +1,131 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use egui::Theme;
+use egui_theme_switch::system_theme::{DesktopSettingsFiles, SystemThemeSource};
+use std::fs::{self, File};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+use tempfile::TempDir;
+
+const GTK_DARK: &str = "[Settings]\ngtk-application-prefer-dark-theme=1\n";
+const GTK_LIGHT: &str = "[Settings]\ngtk-application-prefer-dark-theme=0\n";
+
+#[test]
+fn kdeglobals_color_scheme_name() {
+    let config_dir = config_dir_with(&[("kdeglobals", "[General]\nColorScheme=BreezeDark\n")]);
+    assert_eq!(read_theme(&config_dir), Some(Theme::Dark));
+
+    let config_dir = config_dir_with(&[("kdeglobals", "[General]\nColorScheme=BreezeLight\n")]);
+    assert_eq!(read_theme(&config_dir), Some(Theme::Light));
+}
+
+#[test]
+fn kdeglobals_window_background() {
+    let kdeglobals = "[General]\n\
+        ColorScheme=Midnight\n\
+        \n\
+        [Colors:Window]\n\
+        BackgroundNormal=32,35,38\n";
+    let config_dir = config_dir_with(&[("kdeglobals", kdeglobals)]);
+    assert_eq!(read_theme(&config_dir), Some(Theme::Dark));
+
+    let kdeglobals = "[Colors:Window]\nBackgroundNormal=239,240,241\n";
+    let config_dir = config_dir_with(&[("kdeglobals", kdeglobals)]);
+    assert_eq!(read_theme(&config_dir), Some(Theme::Light));
+
+    let config_dir = config_dir_with(&[("kdeglobals", "[General]\nName=Breeze\n")]);
+    assert_eq!(read_theme(&config_dir), None);
+}
+
+#[test]
+fn gtk_4_wins_over_gtk_3() {
+    let config_dir = config_dir_with(&[
+        ("gtk-4.0/settings.ini", GTK_LIGHT),
+        ("gtk-3.0/settings.ini", GTK_DARK),
+    ]);
+    assert_eq!(read_theme(&config_dir), Some(Theme::Light));
+
+    // Settings without an opinion fall through to the next file.
+    let config_dir = config_dir_with(&[
+        (
+            "gtk-4.0/settings.ini",
+            "[Settings]\ngtk-font-name=Cantarell 11\n",
+        ),
+        (
+            "gtk-3.0/settings.ini",
+            "[Settings]\ngtk-theme-name=Adwaita-dark\n",
+        ),
+    ]);
+    assert_eq!(read_theme(&config_dir), Some(Theme::Dark));
+}
+
+#[test]
+fn gtk_wins_over_kdeglobals() {
+    let config_dir = config_dir_with(&[
+        ("gtk-3.0/settings.ini", GTK_LIGHT),
+        ("kdeglobals", "[General]\nColorScheme=BreezeDark\n"),
+    ]);
+    assert_eq!(read_theme(&config_dir), Some(Theme::Light));
+}
+
+#[test]
+fn changes_are_picked_up() {
+    let config_dir = config_dir_with(&[]);
+    let source = DesktopSettingsFiles::with_config_dir_and_interval(
+        config_dir.path(),
+        Duration::from_millis(10),
+    );
+    assert_eq!(source.system_theme(), None);
+
+    write(&config_dir, "gtk-3.0/settings.ini", GTK_DARK);
+    wait_for(&source, Some(Theme::Dark));
+
+    // The file is only re-read when its modification time changes.
+    write(&config_dir, "gtk-3.0/settings.ini", GTK_LIGHT);
+    let file = File::options()
+        .write(true)
+        .open(config_dir.path().join("gtk-3.0/settings.ini"))
+        .unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(10))
+        .unwrap();
+    wait_for(&source, Some(Theme::Light));
+
+    fs::remove_file(config_dir.path().join("gtk-3.0/settings.ini")).unwrap();
+    wait_for(&source, None);
+}
+
+#[test]
+fn no_opinion_without_a_config_dir() {
+    std::env::remove_var("XDG_CONFIG_HOME");
+    std::env::remove_var("HOME");
+    assert_eq!(DesktopSettingsFiles::new().system_theme(), None);
+}
+
+fn config_dir_with(files: &[(&str, &str)]) -> TempDir {
+    let config_dir = tempfile::tempdir().unwrap();
+    for (path, contents) in files {
+        write(&config_dir, path, contents);
+    }
+    config_dir
+}
+
+fn write(config_dir: &TempDir, path: &str, contents: &str) {
+    let path = config_dir.path().join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, contents).unwrap();
+}
+
+fn read_theme(config_dir: &TempDir) -> Option<Theme> {
+    DesktopSettingsFiles::read_theme(config_dir.path())
+}
+
+fn wait_for(source: &DesktopSettingsFiles, theme: Option<Theme>) {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while source.system_theme() != theme {
+        assert!(
+            Instant::now() < deadline,
+            "the theme didn't change to {theme:?}"
+        );
+        thread::sleep(Duration::from_millis(10));
+    }
+}
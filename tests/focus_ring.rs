@@ -0,0 +1,186 @@
+#![cfg(feature = "testing")]
+
+use egui::epaint::{ClippedShape, Color32, Shape};
+use egui::{CentralPanel, Context, FullOutput, Key, Modifiers, ScrollArea, ThemePreference};
+use egui_theme_switch::testing::{self, Harness};
+use egui_theme_switch::ThemeSwitch;
+
+#[test]
+fn keyboard_focus_shows_a_ring() {
+    let mut harness = Harness::new();
+    let output = harness.run(show);
+    assert_eq!(focus_rings(&harness, &output), 0);
+
+    harness.press_key(Key::Tab, Modifiers::NONE);
+    let output = harness.run(show);
+    assert!(harness.ctx().memory(|m| m.focused()).is_some());
+    assert_eq!(focus_rings(&harness, &output), 1);
+}
+
+#[test]
+fn clicking_hides_the_ring() {
+    let mut harness = Harness::new();
+    harness.run(show);
+    harness.press_key(Key::Tab, Modifiers::NONE);
+    harness.run(show);
+
+    // The selected button is focused and stays focused when it's clicked.
+    harness.click_option(ThemePreference::Dark);
+    let output = harness.run(show);
+    assert_eq!(focus_rings(&harness, &output), 0);
+
+    // Until the keyboard is used again.
+    // Tab wraps around to the same widget, which takes one more pass.
+    harness.press_key(Key::Tab, Modifiers::NONE);
+    harness.run(show);
+    let output = harness.run(show);
+    assert_eq!(focus_rings(&harness, &output), 1);
+}
+
+#[test]
+fn focusing_with_the_label_shows_no_ring() {
+    let mut harness = Harness::new();
+    let show = |ctx: &Context| {
+        CentralPanel::default().show(ctx, |ui| {
+            let mut preference = ThemePreference::System;
+            ui.add(ThemeSwitch::new(&mut preference).label("Appearance"));
+        });
+    };
+    harness.run(show);
+
+    // The label is left of the switch.
+    let switch = testing::switches(harness.ctx()).remove(0).rect;
+    harness.click(switch.left_center() - egui::vec2(10.0, 0.0));
+    let output = harness.run(show);
+    assert!(harness.ctx().memory(|m| m.focused()).is_some());
+    assert_eq!(focus_rings(&harness, &output), 0);
+}
+
+#[test]
+fn tab_skips_the_label() {
+    let mut harness = Harness::new();
+    let show = |ctx: &Context| {
+        CentralPanel::default().show(ctx, |ui| {
+            let mut preference = ThemePreference::Dark;
+            ui.add(ThemeSwitch::new(&mut preference).label("Appearance"));
+        });
+    };
+    harness.run(show);
+
+    harness.press_key(Key::Tab, Modifiers::NONE);
+    harness.run(show);
+    let focused = harness
+        .ctx()
+        .memory(|m| m.focused())
+        .expect("something is focused");
+    let switch = testing::switches(harness.ctx()).remove(0);
+    assert!(
+        focused == switch.id || switch.options.iter().any(|option| option.id == focused),
+        "the switch is focused, not its label"
+    );
+}
+
+#[test]
+fn keyboard_use_is_noticed_while_scrolled_out_of_view() {
+    let mut harness = Harness::new();
+    let show = |ctx: &Context, scroll_offset: f32| {
+        CentralPanel::default().show(ctx, |ui| {
+            ScrollArea::vertical()
+                .max_height(50.0)
+                // Otherwise the scroll area would be focused first.
+                .drag_to_scroll(false)
+                .vertical_scroll_offset(scroll_offset)
+                .show(ui, |ui| {
+                    ui.add_space(500.0);
+                    let mut preference = ThemePreference::Dark;
+                    ui.add(ThemeSwitch::new(&mut preference));
+                });
+        });
+    };
+    harness.run(|ctx| show(ctx, 0.0));
+
+    harness.press_key(Key::Tab, Modifiers::NONE);
+    harness.run(|ctx| show(ctx, 0.0));
+    harness.run(|ctx| show(ctx, 0.0));
+    let switch = testing::switches(harness.ctx()).remove(0);
+    let selected = switch
+        .option(&ThemePreference::Dark)
+        .map(|option| option.id);
+    assert_eq!(harness.ctx().memory(|m| m.focused()), selected);
+
+    // Scrolling the switch into view isn't a click, so the ring shows up.
+    let output = harness.run(|ctx| show(ctx, 500.0));
+    assert_eq!(focus_rings(&harness, &output), 1);
+}
+
+#[test]
+fn switch_is_highlighted_while_a_button_has_focus() {
+    let mut harness = Harness::new();
+    let output = harness.run(show);
+    let widgets = harness.ctx().style().visuals.widgets.clone();
+    assert_eq!(pill_fill(&harness, &output), widgets.inactive.bg_fill);
+
+    harness.press_key(Key::Tab, Modifiers::NONE);
+    let output = harness.run(show);
+    assert_eq!(pill_fill(&harness, &output), widgets.hovered.bg_fill);
+}
+
+#[test]
+fn compact_switch_has_one_ring() {
+    let mut harness = Harness::new();
+    let show = |ctx: &Context| {
+        CentralPanel::default().show(ctx, |ui| {
+            ui.allocate_ui(egui::vec2(40.0, 40.0), |ui| {
+                let mut preference = ThemePreference::Dark;
+                ui.add(ThemeSwitch::new(&mut preference).responsive(true));
+            });
+        });
+    };
+    harness.run(show);
+    assert!(testing::switches(harness.ctx())[0].collapsed);
+
+    harness.press_key(Key::Tab, Modifiers::NONE);
+    let output = harness.run(show);
+    assert!(harness.ctx().memory(|m| m.focused()).is_some());
+    assert_eq!(focus_rings(&harness, &output), 1);
+}
+
+fn show(ctx: &Context) {
+    CentralPanel::default().show(ctx, |ui| {
+        let mut preference = ThemePreference::Dark;
+        ui.add(ThemeSwitch::new(&mut preference));
+    });
+}
+
+/// Outlines without a fill in the focus stroke's colour that surround a whole button.
+fn focus_rings(harness: &Harness, output: &FullOutput) -> usize {
+    let style = harness.ctx().style();
+    let ring = style.visuals.selection.stroke;
+    let button_radius = 0.5 * style.spacing.interact_size.y;
+    output
+        .shapes
+        .iter()
+        .filter(|ClippedShape { shape, .. }| match shape {
+            Shape::Rect(rect) => rect.fill == Color32::TRANSPARENT && rect.stroke == ring,
+            Shape::Circle(circle) => {
+                circle.fill == Color32::TRANSPARENT
+                    && circle.stroke.color == ring.color
+                    && circle.radius > button_radius
+            }
+            _ => false,
+        })
+        .count()
+}
+
+/// The fill of the switch's background.
+fn pill_fill(harness: &Harness, output: &FullOutput) -> Color32 {
+    let switch = testing::switches(harness.ctx()).remove(0).rect;
+    output
+        .shapes
+        .iter()
+        .find_map(|ClippedShape { shape, .. }| match shape {
+            Shape::Rect(rect) if rect.rect == switch => Some(rect.fill),
+            _ => None,
+        })
+        .expect("the switch's background is painted")
+}
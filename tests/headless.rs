@@ -0,0 +1,135 @@
+#![cfg(feature = "headless")]
+
+use egui::emath::vec2;
+use egui::epaint::{Color32, ColorImage};
+use egui::{Theme, ThemePreference, Visuals};
+use egui_theme_switch::headless::Renderer;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+/// How much a channel may differ before the pixel counts as changed.
+const CHANNEL_TOLERANCE: u8 = 8;
+/// How many pixels may change, e.g. because of floating point differences between platforms.
+const MAX_CHANGED_PIXELS: f32 = 0.005;
+
+#[test]
+fn switch_matches_snapshots() {
+    let preferences = [
+        ("system", ThemePreference::System),
+        ("dark", ThemePreference::Dark),
+        ("light", ThemePreference::Light),
+    ];
+    for (theme_name, theme) in [("dark", Theme::Dark), ("light", Theme::Light)] {
+        for (preference_name, preference) in preferences {
+            let image = renderer(theme).render_switch(preference);
+            assert_snapshot(&format!("{theme_name}_theme_{preference_name}"), &image);
+        }
+    }
+}
+
+#[test]
+fn canvas_is_filled_with_the_panel_colour() {
+    for (theme, visuals) in [
+        (Theme::Dark, Visuals::dark()),
+        (Theme::Light, Visuals::light()),
+    ] {
+        let image = renderer(theme).render_switch(ThemePreference::Dark);
+        assert_eq!(image.size, [160, 56]);
+        assert_eq!(image[(0, 0)], visuals.panel_fill);
+        assert_eq!(image[(159, 55)], visuals.panel_fill);
+    }
+}
+
+#[test]
+fn selected_button_has_the_selection_colour() {
+    // The switch is 64 × 20 points, so the middle button is in the center of the canvas.
+    let image = renderer(Theme::Dark).render_switch(ThemePreference::Dark);
+    let below_icon = image[(80, 28 + 14)];
+    assert_eq!(below_icon, Visuals::dark().selection.bg_fill);
+
+    let image = renderer(Theme::Dark).render_switch(ThemePreference::Light);
+    let below_icon = image[(80, 28 + 14)];
+    assert_eq!(below_icon, Visuals::dark().widgets.inactive.bg_fill);
+}
+
+fn renderer(theme: Theme) -> Renderer {
+    Renderer::new(theme)
+        .size(vec2(80.0, 28.0))
+        .pixels_per_point(2.0)
+}
+
+/// Compares the image with `tests/snapshots/{name}.png`.
+/// Set `UPDATE_SNAPSHOTS=1` to accept the new images.
+fn assert_snapshot(name: &str, image: &ColorImage) {
+    let path = snapshot_path(&format!("{name}.png"));
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        write_png(&path, image);
+        return;
+    }
+
+    let expected = read_png(&path);
+    assert_eq!(expected.size, image.size, "{name} has a different size");
+    let changed = expected
+        .pixels
+        .iter()
+        .zip(&image.pixels)
+        .filter(|(a, b)| max_channel_difference(**a, **b) > CHANNEL_TOLERANCE)
+        .count();
+    let max_changed = (MAX_CHANGED_PIXELS * image.pixels.len() as f32) as usize;
+    if changed > max_changed {
+        let new_path = snapshot_path(&format!("{name}.new.png"));
+        write_png(&new_path, image);
+        panic!(
+            "{changed} pixels of {name} changed (at most {max_changed} may change), see {}",
+            new_path.display()
+        );
+    }
+}
+
+fn max_channel_difference(a: Color32, b: Color32) -> u8 {
+    a.to_array()
+        .into_iter()
+        .zip(b.to_array())
+        .map(|(a, b)| a.abs_diff(b))
+        .max()
+        .unwrap_or_default()
+}
+
+fn snapshot_path(file_name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(file_name)
+}
+
+fn read_png(path: &Path) -> ColorImage {
+    let file = File::open(path).unwrap_or_else(|error| {
+        panic!(
+            "failed to open {}: {error}, run with UPDATE_SNAPSHOTS=1 to create it",
+            path.display()
+        )
+    });
+    let mut reader = png::Decoder::new(file).read_info().expect("valid png");
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).expect("valid png");
+    assert_eq!(info.color_type, png::ColorType::Rgba);
+    let size = [info.width as usize, info.height as usize];
+    ColorImage::from_rgba_unmultiplied(size, &buffer[..info.buffer_size()])
+}
+
+fn write_png(path: &Path, image: &ColorImage) {
+    let file = BufWriter::new(File::create(path).expect("writable snapshot"));
+    let [width, height] = image.size;
+    let mut encoder = png::Encoder::new(file, width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let data: Vec<u8> = image
+        .pixels
+        .iter()
+        .flat_map(|pixel| pixel.to_srgba_unmultiplied())
+        .collect();
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&data))
+        .expect("writable snapshot");
+}
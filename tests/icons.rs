@@ -0,0 +1,163 @@
+use egui::emath::{pos2, vec2, Rect};
+use egui::epaint::{ClippedShape, Color32, Shape};
+use egui::{Context, LayerId, Painter};
+use egui_theme_switch::icons::{Icon, IconStyle};
+
+const ICONS: [Icon; 6] = [
+    Icon::Sun,
+    Icon::Moon,
+    Icon::Cogwheel,
+    Icon::Monitor,
+    Icon::Contrast,
+    Icon::Automatic,
+];
+const STYLES: [IconStyle; 2] = [IconStyle::Filled, IconStyle::Outline];
+// The hairline around filled shapes and the approximation of the arcs.
+const TOLERANCE: f32 = 1.0;
+
+#[test]
+fn icons_are_centered_in_their_rect() {
+    // Wider than tall, so the icon is as large as the rect is high.
+    let rect = Rect::from_min_size(pos2(10.0, 10.0), vec2(60.0, 40.0));
+    for icon in ICONS {
+        for style in STYLES {
+            let shapes = paint(|painter| icon.paint_in_rect(painter, rect, Color32::WHITE, style));
+            let bounds = bounds(&shapes);
+            assert!(
+                rect.expand(TOLERANCE).contains_rect(bounds),
+                "{icon:?} ({style:?}) at {bounds:?} is outside of {rect:?}"
+            );
+            let circle = Rect::from_center_size(rect.center(), vec2(40.0, 40.0));
+            assert!(
+                circle.expand(TOLERANCE).contains_rect(bounds),
+                "{icon:?} ({style:?}) at {bounds:?} is outside of its circle"
+            );
+        }
+    }
+}
+
+#[test]
+fn outline_style_differs_from_filled() {
+    for icon in ICONS {
+        let [filled, outline] = STYLES.map(|style| {
+            paint(|painter| icon.paint(painter, pos2(50.0, 50.0), 20.0, Color32::WHITE, style))
+        });
+        assert!(!filled.is_empty(), "{icon:?} paints nothing");
+        assert_ne!(filled, outline, "{icon:?} looks the same in both styles");
+    }
+}
+
+#[test]
+fn custom_line_width_is_used() {
+    let shapes = paint(|painter| {
+        Icon::Moon.paint_with_line_width(
+            painter,
+            pos2(50.0, 50.0),
+            20.0,
+            Color32::WHITE,
+            IconStyle::Outline,
+            3.0,
+        );
+    });
+    assert!(!shapes.is_empty());
+    for shape in &shapes {
+        if let Shape::Path(path) = shape {
+            assert_eq!(path.stroke.width, 3.0);
+        }
+    }
+}
+
+#[test]
+fn outlined_cogwheel_is_one_closed_line() {
+    let shapes = paint(|painter| {
+        Icon::Cogwheel.paint(
+            painter,
+            pos2(50.0, 50.0),
+            20.0,
+            Color32::WHITE,
+            IconStyle::Outline,
+        );
+    });
+    let paths: Vec<_> = shapes
+        .iter()
+        .filter_map(|shape| match shape {
+            Shape::Path(path) => Some(path),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(paths.len(), 1, "{shapes:?}");
+    let path = paths[0];
+    assert!(path.closed);
+    assert_eq!(path.fill, Color32::TRANSPARENT);
+    // A repeated point gives the stroke a spike where the line turns back on itself.
+    let next = path.points.iter().cycle().skip(1);
+    for (a, b) in path.points.iter().zip(next) {
+        assert!(a.distance(*b) > 0.01, "{a:?} is repeated");
+    }
+}
+
+#[test]
+fn glyphs_scale_from_12_to_64_points() {
+    let line_widths = [None, Some(1.0 / 8.0)];
+    for icon in ICONS {
+        for style in STYLES {
+            for line_width in line_widths {
+                let bounds = [12.0, 24.0, 64.0].map(|size| {
+                    let rect = Rect::from_min_size(pos2(10.0, 10.0), vec2(size, size));
+                    let shapes = paint(|painter| match line_width {
+                        Some(line_width) => icon.paint_with_line_width(
+                            painter,
+                            rect.center(),
+                            0.5 * size,
+                            Color32::WHITE,
+                            style,
+                            line_width * 0.5 * size,
+                        ),
+                        None => icon.paint_in_rect(painter, rect, Color32::WHITE, style),
+                    });
+                    let bounds = bounds(&shapes);
+                    assert!(
+                        rect.expand(0.5).contains_rect(bounds),
+                        "{icon:?} ({style:?}) at {size}: {bounds:?} is outside of {rect:?}"
+                    );
+                    // Relative to the rect, so that the sizes can be compared.
+                    Rect::from_min_max(
+                        ((bounds.min - rect.min) / size).to_pos2(),
+                        ((bounds.max - rect.min) / size).to_pos2(),
+                    )
+                });
+                // Lines are at least a pixel wide, which makes small icons a bit bolder.
+                let [small, medium, large] = bounds;
+                for (size, bounds) in [(12.0, small), (24.0, medium)] {
+                    let difference = (bounds.min - large.min)
+                        .abs()
+                        .max((bounds.max - large.max).abs())
+                        .max_elem();
+                    assert!(
+                        difference <= 1.0 / size,
+                        "{icon:?} ({style:?}) at {size} is not a scaled version: {bounds:?} vs. {large:?}"
+                    );
+                }
+            }
+        }
+    }
+}
+
+fn paint(add_contents: impl Fn(&Painter)) -> Vec<Shape> {
+    let ctx = Context::default();
+    let output = ctx.run(Default::default(), |ctx| {
+        add_contents(&ctx.layer_painter(LayerId::background()));
+    });
+    output
+        .shapes
+        .into_iter()
+        .map(|ClippedShape { shape, .. }| shape)
+        .collect()
+}
+
+fn bounds(shapes: &[Shape]) -> Rect {
+    shapes
+        .iter()
+        .map(Shape::visual_bounding_rect)
+        .fold(Rect::NOTHING, Rect::union)
+}
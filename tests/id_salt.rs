@@ -0,0 +1,57 @@
+#![cfg(feature = "testing")]
+
+use egui::{CentralPanel, Context, ThemePreference};
+use egui_theme_switch::testing::{self, Harness, SwitchLayout};
+use egui_theme_switch::ThemeSwitch;
+
+#[test]
+fn salted_id_does_not_depend_on_the_layout() {
+    let mut harness = Harness::new();
+    harness.run(|ctx| show(ctx, 0, None));
+    let before = switch(&harness);
+
+    // Widgets in front of the switch and a label wrapping it change the automatic ids.
+    harness.run(|ctx| show(ctx, 3, Some("Appearance")));
+    let after = switch(&harness);
+
+    assert_eq!(before.id, after.id);
+    for (n, option) in after.options.iter().enumerate() {
+        assert_eq!(option.id, before.id.with(n));
+    }
+}
+
+#[test]
+fn switches_with_different_salts_have_different_ids() {
+    let mut harness = Harness::new();
+    harness.run(|ctx| {
+        CentralPanel::default().show(ctx, |ui| {
+            for salt in ["first", "second"] {
+                let mut preference = ThemePreference::System;
+                ui.add(ThemeSwitch::new(&mut preference).id_salt(salt));
+            }
+        });
+    });
+
+    let switches = testing::switches(harness.ctx());
+    assert_eq!(switches.len(), 2);
+    assert_ne!(switches[0].id, switches[1].id);
+}
+
+fn show(ctx: &Context, widgets_before: usize, label: Option<&str>) {
+    CentralPanel::default().show(ctx, |ui| {
+        for _ in 0..widgets_before {
+            ui.label("Before");
+            let _ = ui.button("Before");
+        }
+        let mut preference = ThemePreference::System;
+        let switch = ThemeSwitch::new(&mut preference).id_salt("theme");
+        match label {
+            Some(label) => ui.add(switch.label(label)),
+            None => ui.add(switch),
+        };
+    });
+}
+
+fn switch(harness: &Harness) -> SwitchLayout<ThemePreference> {
+    testing::switches(harness.ctx()).remove(0)
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "testing")]
+
+use egui::{CentralPanel, Context, Key, Modifiers, ThemePreference};
+use egui_theme_switch::testing::{self, Harness};
+use egui_theme_switch::ThemeSwitch;
+
+#[test]
+fn tab_focuses_the_selected_button() {
+    let mut harness = Harness::new();
+    let mut value = ThemePreference::Dark;
+    harness.run(|ctx| show(ctx, &mut value));
+
+    harness.press_key(Key::Tab, Modifiers::NONE);
+    harness.run(|ctx| show(ctx, &mut value));
+    assert_eq!(
+        focused(&harness),
+        option_id(&harness, ThemePreference::Dark)
+    );
+}
+
+#[test]
+fn arrow_keys_move_the_selection_and_focus() {
+    let mut harness = Harness::new();
+    let mut value = ThemePreference::Dark;
+    harness.run(|ctx| show(ctx, &mut value));
+    harness.press_key(Key::Tab, Modifiers::NONE);
+    harness.run(|ctx| show(ctx, &mut value));
+
+    harness.press_key(Key::ArrowRight, Modifiers::NONE);
+    harness.run(|ctx| show(ctx, &mut value));
+    assert_eq!(value, ThemePreference::Light);
+    assert_eq!(
+        focused(&harness),
+        option_id(&harness, ThemePreference::Light)
+    );
+
+    harness.press_key(Key::ArrowLeft, Modifiers::NONE);
+    harness.run(|ctx| show(ctx, &mut value));
+    assert_eq!(value, ThemePreference::Dark);
+    assert_eq!(
+        focused(&harness),
+        option_id(&harness, ThemePreference::Dark)
+    );
+}
+
+fn show(ctx: &Context, value: &mut ThemePreference) {
+    CentralPanel::default().show(ctx, |ui| {
+        ui.add(ThemeSwitch::new(value));
+    });
+}
+
+fn focused(harness: &Harness) -> Option<egui::Id> {
+    harness.ctx().memory(|m| m.focused())
+}
+
+fn option_id(harness: &Harness, value: ThemePreference) -> Option<egui::Id> {
+    let switch = testing::switches(harness.ctx()).remove(0);
+    switch.option(&value).map(|option| option.id)
+}
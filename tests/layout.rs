@@ -0,0 +1,145 @@
+#![cfg(feature = "testing")]
+
+use egui::emath::vec2;
+use egui::epaint::{CircleShape, Shape};
+use egui::{Align, CentralPanel, Context, FullOutput, Layout, Response, ThemePreference};
+use egui_theme_switch::testing::{self, Harness};
+use egui_theme_switch::ThemeSwitch;
+
+#[test]
+fn options_are_mirrored_in_right_to_left_layouts() {
+    let mut harness = Harness::new();
+    let mut value = ThemePreference::Dark;
+    harness.run(|ctx| show(ctx, &mut value, true));
+
+    let switch = &testing::switches(harness.ctx())[0];
+    let left_edges: Vec<_> = switch.options.iter().map(|o| o.rect.left()).collect();
+    assert!(left_edges.windows(2).all(|w| w[0] > w[1]), "{left_edges:?}");
+    assert_eq!(switch.options[0].rect.right(), switch.rect.right());
+
+    harness.click(switch.options[2].rect.center());
+    harness.run(|ctx| show(ctx, &mut value, true));
+    assert_eq!(value, ThemePreference::Light);
+}
+
+#[test]
+fn indicator_animates_towards_the_leading_edge() {
+    let mut harness = Harness::new();
+    let mut value = ThemePreference::Dark;
+    harness.run(|ctx| show(ctx, &mut value, true));
+    let switch = &testing::switches(harness.ctx())[0];
+    let dark = switch.options[1].rect.center().x;
+    let light = switch.options[2].rect.center().x;
+
+    harness.click_option(ThemePreference::Light);
+    harness.run(|ctx| show(ctx, &mut value, true));
+    let output = harness.run(|ctx| show(ctx, &mut value, true));
+    let x = indicator(&harness, output).center.x;
+    assert!(light < x && x < dark, "{light} < {x} < {dark}");
+}
+
+#[test]
+fn indicator_does_not_animate_when_mirrored() {
+    let mut value = ThemePreference::Light;
+    let mut mirrored = Harness::new();
+    mirrored.run(|ctx| show(ctx, &mut value, false));
+    mirrored.run(|ctx| show(ctx, &mut value, true));
+    let output = mirrored.run(|ctx| show(ctx, &mut value, true));
+    let x = indicator(&mirrored, output).center.x;
+
+    let mut right_to_left = Harness::new();
+    let output = right_to_left.run(|ctx| show(ctx, &mut value, true));
+    assert_eq!(x, indicator(&right_to_left, output).center.x);
+}
+
+#[test]
+fn responsive_switch_collapses_when_it_does_not_fit() {
+    let mut harness = Harness::new();
+    let mut value = ThemePreference::Dark;
+    for (max_width, collapsed) in [(200.0, false), (40.0, true)] {
+        harness.run(|ctx| show_responsive(ctx, &mut value, max_width, &mut None));
+        let switch = &testing::switches(harness.ctx())[0];
+        assert_eq!(switch.collapsed, collapsed);
+        assert_eq!(switch.options.is_empty(), collapsed);
+        if collapsed {
+            assert_eq!(switch.rect.width(), switch.rect.height());
+        }
+    }
+}
+
+#[test]
+fn collapsed_switch_picks_a_value_from_the_popup() {
+    let mut harness = Harness::new();
+    let mut value = ThemePreference::Dark;
+    let mut response = None;
+    harness.run(|ctx| show_responsive(ctx, &mut value, 40.0, &mut response));
+
+    let rect = testing::switches(harness.ctx())[0].rect;
+    harness.click(rect.center());
+    harness.run(|ctx| show_responsive(ctx, &mut value, 40.0, &mut response));
+    harness.run(|ctx| show_responsive(ctx, &mut value, 40.0, &mut response));
+    let labels: Vec<_> = testing::switches(harness.ctx())[0]
+        .options
+        .iter()
+        .map(|o| o.label)
+        .collect();
+    assert_eq!(labels, ["Follow System", "Dark", "Light"]);
+
+    harness.click_option(ThemePreference::Light);
+    harness.run(|ctx| show_responsive(ctx, &mut value, 40.0, &mut response));
+    assert_eq!(value, ThemePreference::Light);
+    assert!(response.as_ref().is_some_and(Response::changed));
+
+    // The popup closes after picking a value.
+    harness.run(|ctx| show_responsive(ctx, &mut value, 40.0, &mut response));
+    assert!(testing::switches(harness.ctx())[0].options.is_empty());
+    assert!(!response.as_ref().is_some_and(Response::changed));
+}
+
+#[test]
+fn switch_is_not_collapsed_by_default() {
+    let mut harness = Harness::new();
+    let mut value = ThemePreference::Dark;
+    harness.run(|ctx| {
+        CentralPanel::default().show(ctx, |ui| {
+            ui.allocate_ui(vec2(40.0, 40.0), |ui| ui.add(ThemeSwitch::new(&mut value)));
+        });
+    });
+    assert!(!testing::switches(harness.ctx())[0].collapsed);
+}
+
+fn show_responsive(
+    ctx: &Context,
+    value: &mut ThemePreference,
+    max_width: f32,
+    response: &mut Option<Response>,
+) {
+    CentralPanel::default().show(ctx, |ui| {
+        ui.allocate_ui(vec2(max_width, 40.0), |ui| {
+            *response = Some(ui.add(ThemeSwitch::new(value).responsive(true)));
+        });
+    });
+}
+
+fn show(ctx: &Context, value: &mut ThemePreference, right_to_left: bool) {
+    CentralPanel::default().show(ctx, |ui| {
+        let layout = if right_to_left {
+            Layout::right_to_left(Align::Center)
+        } else {
+            Layout::left_to_right(Align::Center)
+        };
+        ui.with_layout(layout, |ui| ui.add(ThemeSwitch::new(value)));
+    });
+}
+
+fn indicator(harness: &Harness, output: FullOutput) -> CircleShape {
+    let fill = harness.ctx().style().visuals.selection.bg_fill;
+    output
+        .shapes
+        .into_iter()
+        .find_map(|clipped| match clipped.shape {
+            Shape::Circle(circle) if circle.fill == fill => Some(circle),
+            _ => None,
+        })
+        .expect("the active indicator is painted")
+}
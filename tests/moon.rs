@@ -0,0 +1,98 @@
+use egui::emath::{pos2, Pos2, Rect};
+use egui::epaint::{ClippedShape, Color32, Shape};
+use egui::{Context, LayerId, Painter};
+use egui_theme_switch::icons::{Icon, IconStyle, MoonPhase};
+use std::f32::consts::TAU;
+
+const CENTER: Pos2 = pos2(50.0, 50.0);
+const RADIUS: f32 = 20.0;
+// The hairline around filled shapes and the approximation of the arcs.
+const TOLERANCE: f32 = 1.0;
+
+#[test]
+fn new_moon_is_an_outline() {
+    for style in [IconStyle::Filled, IconStyle::Outline] {
+        let shapes = paint(MoonPhase::new(0.0), style);
+        assert_eq!(shapes.len(), 1);
+        let Shape::Circle(circle) = &shapes[0] else {
+            panic!("expected a circle, got {:?}", shapes[0]);
+        };
+        assert_eq!(circle.fill, Color32::TRANSPARENT);
+        assert!(circle.stroke.width > 0.0);
+        assert_eq!(circle.center, CENTER);
+        assert!(circle.radius + 0.5 * circle.stroke.width <= RADIUS);
+    }
+}
+
+#[test]
+fn half_moon_is_the_right_half() {
+    let bounds = bounds(MoonPhase::new(0.5));
+    assert_close(bounds.left(), CENTER.x);
+    assert_close(bounds.right(), CENTER.x + RADIUS);
+    assert_close(bounds.top(), CENTER.y - RADIUS);
+    assert_close(bounds.bottom(), CENTER.y + RADIUS);
+}
+
+#[test]
+fn full_moon_is_the_whole_circle() {
+    let bounds = bounds(MoonPhase::new(1.0));
+    let circle = Rect::from_center_size(CENTER, egui::Vec2::splat(2.0 * RADIUS));
+    assert_close(bounds.left(), circle.left());
+    assert_close(bounds.right(), circle.right());
+    assert_close(bounds.top(), circle.top());
+    assert_close(bounds.bottom(), circle.bottom());
+}
+
+#[test]
+fn waxing_moon_is_lit_from_the_right_and_waning_from_the_left() {
+    // A crescent reaches the center only at the poles.
+    let waxing = bounds(MoonPhase::new(0.25));
+    assert_close(waxing.left(), CENTER.x);
+    assert_close(waxing.right(), CENTER.x + RADIUS);
+
+    let waning = bounds(MoonPhase::new(0.25).waning());
+    assert_close(waning.left(), CENTER.x - RADIUS);
+    assert_close(waning.right(), CENTER.x);
+}
+
+#[test]
+fn moon_icon_is_a_crescent_phase() {
+    let icon = paint_with(|painter| {
+        Icon::Moon.paint(painter, CENTER, RADIUS, Color32::WHITE, IconStyle::Filled);
+    });
+    let crescent = paint(
+        MoonPhase::new(0.4).with_tilt(0.365 * TAU),
+        IconStyle::Filled,
+    );
+    assert_eq!(icon, crescent);
+}
+
+fn paint(moon: MoonPhase, style: IconStyle) -> Vec<Shape> {
+    paint_with(|painter| moon.paint(painter, CENTER, RADIUS, Color32::WHITE, style))
+}
+
+fn paint_with(add_contents: impl Fn(&Painter)) -> Vec<Shape> {
+    let ctx = Context::default();
+    let output = ctx.run(Default::default(), |ctx| {
+        add_contents(&ctx.layer_painter(LayerId::background()));
+    });
+    output
+        .shapes
+        .into_iter()
+        .map(|ClippedShape { shape, .. }| shape)
+        .collect()
+}
+
+fn bounds(moon: MoonPhase) -> Rect {
+    paint(moon, IconStyle::Filled)
+        .iter()
+        .map(Shape::visual_bounding_rect)
+        .fold(Rect::NOTHING, Rect::union)
+}
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!(
+        (actual - expected).abs() <= TOLERANCE,
+        "{actual} is not close to {expected}"
+    );
+}
@@ -0,0 +1,189 @@
+#![cfg(feature = "testing")]
+
+use egui::{CentralPanel, Context, Response, Theme, ThemePreference, Visuals};
+use egui_theme_switch::testing::{self, Harness};
+use egui_theme_switch::{GlobalThemeSwitch, ThemeTransition};
+
+const DELAY: f32 = 0.1;
+
+#[test]
+fn hovering_previews_the_theme_after_a_delay() {
+    let mut harness = light_harness();
+    let mut response = None;
+    harness.run(|ctx| show(ctx, &mut response));
+
+    hover_option(&mut harness, ThemePreference::Dark);
+    harness.run(|ctx| show(ctx, &mut response));
+    assert_eq!(harness.ctx().style().visuals, Visuals::light());
+
+    run_for(&mut harness, DELAY, &mut response);
+    assert_eq!(harness.ctx().style().visuals, Visuals::dark());
+    // The preview is not a change.
+    assert!(!response.as_ref().is_some_and(Response::changed));
+    assert_eq!(harness.ctx().theme(), Theme::Light);
+    assert_eq!(
+        harness.ctx().options(|o| o.theme_preference),
+        ThemePreference::Light
+    );
+
+    harness.hover(egui::pos2(700.0, 500.0));
+    harness.run(|ctx| show(ctx, &mut response));
+    assert_eq!(harness.ctx().style().visuals, Visuals::light());
+}
+
+#[test]
+fn clicking_commits_the_preview() {
+    let mut harness = light_harness();
+    let mut response = None;
+    harness.run(|ctx| show(ctx, &mut response));
+
+    hover_option(&mut harness, ThemePreference::Dark);
+    run_for(&mut harness, DELAY, &mut response);
+    harness.click_option(ThemePreference::Dark);
+    harness.run(|ctx| show(ctx, &mut response));
+
+    assert!(response.as_ref().is_some_and(Response::changed));
+    assert_eq!(harness.ctx().theme(), Theme::Dark);
+    assert_eq!(harness.ctx().style().visuals, Visuals::dark());
+    // The light style is restored for when the user switches back.
+    assert_eq!(
+        harness.ctx().style_of(Theme::Light).visuals,
+        Visuals::light()
+    );
+}
+
+#[test]
+fn hovering_the_selected_option_previews_nothing() {
+    let mut harness = light_harness();
+    let mut response = None;
+    harness.run(|ctx| show(ctx, &mut response));
+
+    hover_option(&mut harness, ThemePreference::Light);
+    run_for(&mut harness, DELAY, &mut response);
+    assert_eq!(harness.ctx().style().visuals, Visuals::light());
+}
+
+#[test]
+fn previewing_during_a_cross_fade_keeps_the_real_style() {
+    let mut harness = light_harness();
+    let show = |ctx: &Context| {
+        CentralPanel::default().show(ctx, |ui| {
+            ui.add(
+                GlobalThemeSwitch::new()
+                    .transition(ThemeTransition::cross_fade(1.0))
+                    .preview_on_hover(DELAY),
+            );
+        });
+    };
+    harness.run(show);
+
+    harness.click_option(ThemePreference::Dark);
+    harness.run(show);
+    hover_option(&mut harness, ThemePreference::Light);
+    for _ in 0..=(DELAY * 60.0).ceil() as usize + 1 {
+        harness.run(show);
+    }
+    // The preview is shown right away instead of being overwritten by the cross-fade.
+    assert_eq!(harness.ctx().theme(), Theme::Dark);
+    assert_eq!(harness.ctx().style().visuals, Visuals::light());
+
+    harness.hover(egui::pos2(700.0, 500.0));
+    harness.run(show);
+    assert_eq!(harness.ctx().style().visuals, Visuals::dark());
+    assert_eq!(harness.ctx().style_of(Theme::Dark).visuals, Visuals::dark());
+}
+
+#[test]
+fn switch_without_preview_ignores_hovering() {
+    let mut harness = light_harness();
+    let show = |ctx: &Context| {
+        CentralPanel::default().show(ctx, |ui| {
+            ui.add(GlobalThemeSwitch::new());
+        });
+    };
+    harness.run(show);
+
+    hover_option(&mut harness, ThemePreference::Dark);
+    for _ in 0..20 {
+        harness.run(show);
+    }
+    assert_eq!(harness.ctx().style().visuals, Visuals::light());
+}
+
+#[test]
+fn hiding_the_switch_ends_the_preview() {
+    let mut harness = light_harness();
+    let mut response = None;
+    harness.run(|ctx| show(ctx, &mut response));
+
+    hover_option(&mut harness, ThemePreference::Dark);
+    harness.run(|ctx| show(ctx, &mut response));
+    run_for(&mut harness, DELAY, &mut response);
+    assert_eq!(harness.ctx().style().visuals, Visuals::dark());
+
+    // E.g. the menu that contained the switch was closed.
+    harness.run(|ctx| {
+        CentralPanel::default().show(ctx, |_| {});
+    });
+    assert_eq!(harness.ctx().style().visuals, Visuals::light());
+    assert_eq!(
+        harness.ctx().style_of(Theme::Light).visuals,
+        Visuals::light()
+    );
+}
+
+#[test]
+fn other_switches_dont_interrupt_the_preview() {
+    let show = |ctx: &Context| {
+        CentralPanel::default().show(ctx, |ui| {
+            // E.g. one in the menu bar and one in the settings.
+            ui.add(GlobalThemeSwitch::new().preview_on_hover(DELAY));
+            ui.add(GlobalThemeSwitch::new().preview_on_hover(DELAY));
+        });
+    };
+    for hovered_switch in [0, 1] {
+        let mut harness = light_harness();
+        harness.run(show);
+
+        let switch = &testing::switches(harness.ctx())[hovered_switch];
+        let rect = switch.option(&ThemePreference::Dark).unwrap().rect;
+        harness.hover(rect.center());
+        for _ in 0..=(DELAY * 60.0).ceil() as usize + 1 {
+            harness.run(show);
+        }
+        assert_eq!(
+            harness.ctx().style().visuals,
+            Visuals::dark(),
+            "hovering switch {hovered_switch}"
+        );
+
+        harness.hover(egui::pos2(700.0, 500.0));
+        harness.run(show);
+        assert_eq!(harness.ctx().style().visuals, Visuals::light());
+    }
+}
+
+fn light_harness() -> Harness {
+    let harness = Harness::new();
+    harness.ctx().set_theme(ThemePreference::Light);
+    harness
+}
+
+fn hover_option(harness: &mut Harness, value: ThemePreference) {
+    let switch = &testing::switches(harness.ctx())[0];
+    let rect = switch.option(&value).expect("option is shown").rect;
+    harness.hover(rect.center());
+}
+
+/// Runs enough passes for `seconds` to pass.
+fn run_for(harness: &mut Harness, seconds: f32, response: &mut Option<Response>) {
+    for _ in 0..=(seconds * 60.0).ceil() as usize {
+        harness.run(|ctx| show(ctx, response));
+    }
+}
+
+fn show(ctx: &Context, response: &mut Option<Response>) {
+    CentralPanel::default().show(ctx, |ui| {
+        *response = Some(ui.add(GlobalThemeSwitch::new().preview_on_hover(DELAY)));
+    });
+}
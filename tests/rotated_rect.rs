@@ -0,0 +1,139 @@
+use egui::emath::{pos2, vec2, Pos2, Rect, Rot2};
+use egui::epaint::{Color32, Stroke};
+use egui_theme_switch::RotatedRect;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, SQRT_2};
+
+const EPSILON: f32 = 1e-4;
+
+#[test]
+fn square_corners_without_rotation() {
+    let rect = Rect::from_min_max(pos2(10.0, 20.0), pos2(30.0, 60.0));
+    let rotated = RotatedRect::new(rect, 0.0, Rot2::IDENTITY, Pos2::ZERO);
+    assert_points(
+        &rotated.points(1.0),
+        &[
+            rect.left_top(),
+            rect.right_top(),
+            rect.right_bottom(),
+            rect.left_bottom(),
+        ],
+    );
+}
+
+#[test]
+fn quarter_turn_around_the_origin() {
+    let rect = Rect::from_min_max(pos2(10.0, 20.0), pos2(30.0, 60.0));
+    let origin = pos2(10.0, 10.0);
+    let rotated = RotatedRect::new(rect, 0.0, Rot2::from_angle(FRAC_PI_2), origin);
+    // Clockwise on the screen, where y points down.
+    assert_points(
+        &rotated.points(1.0),
+        &[
+            pos2(0.0, 10.0),
+            pos2(0.0, 30.0),
+            pos2(-40.0, 30.0),
+            pos2(-40.0, 10.0),
+        ],
+    );
+}
+
+#[test]
+fn eighth_turn_around_the_center() {
+    let rect = Rect::from_center_size(pos2(50.0, 50.0), vec2(20.0, 20.0));
+    let rotated = RotatedRect::new(rect, 0.0, Rot2::from_angle(FRAC_PI_4), rect.center());
+    let half_diagonal = 10.0 * SQRT_2;
+    assert_points(
+        &rotated.points(1.0),
+        &[
+            pos2(50.0, 50.0 - half_diagonal),
+            pos2(50.0 + half_diagonal, 50.0),
+            pos2(50.0, 50.0 + half_diagonal),
+            pos2(50.0 - half_diagonal, 50.0),
+        ],
+    );
+}
+
+#[test]
+fn rounded_corners_stay_within_a_tenth_of_a_pixel() {
+    let rect = Rect::from_center_size(pos2(50.0, 50.0), vec2(40.0, 60.0));
+    let radius = 10.0;
+    let corner_centers = [
+        pos2(40.0, 30.0),
+        pos2(60.0, 30.0),
+        pos2(60.0, 70.0),
+        pos2(40.0, 70.0),
+    ];
+    let mut point_counts = Vec::new();
+    for pixels_per_point in [1.0, 2.0, 4.0] {
+        let tolerance = 0.1 / pixels_per_point;
+        let points =
+            RotatedRect::new(rect, radius, Rot2::IDENTITY, Pos2::ZERO).points(pixels_per_point);
+        for segment in points.windows(2) {
+            let [a, b] = [segment[0], segment[1]];
+            // Segments along the straight sides don't belong to a corner.
+            let Some(center) = corner_centers
+                .into_iter()
+                .find(|c| is_on_circle(a, *c, radius) && is_on_circle(b, *c, radius))
+            else {
+                continue;
+            };
+            let midpoint = a + 0.5 * (b - a);
+            let error = radius - midpoint.distance(center);
+            assert!(
+                error <= tolerance,
+                "{error} > {tolerance} at {pixels_per_point} pixels per point"
+            );
+        }
+        point_counts.push(points.len());
+    }
+    assert!(
+        point_counts.windows(2).all(|n| n[0] < n[1]),
+        "sharper screens should get more points: {point_counts:?}"
+    );
+}
+
+#[test]
+fn fractional_radii_are_kept() {
+    // E.g. the cogwheel's teeth at the size of the switch's icons.
+    let rect = Rect::from_min_size(pos2(10.0, 10.0), vec2(2.0, 2.0));
+    let radius = 0.3;
+    let corner_center = rect.left_top() + vec2(radius, radius);
+    let points = RotatedRect::new(rect, radius, Rot2::IDENTITY, Pos2::ZERO).points(2.0);
+    assert!(!points.contains(&rect.left_top()));
+    let near_corner = points.iter().filter(|p| p.distance(rect.left_top()) < 0.5);
+    for point in near_corner {
+        assert!(is_on_circle(*point, corner_center, radius), "{point:?}");
+    }
+}
+
+#[test]
+fn corners_are_limited_to_half_of_the_shorter_side() {
+    let rect = Rect::from_center_size(pos2(50.0, 50.0), vec2(20.0, 60.0));
+    let points = RotatedRect::new(rect, 100.0, Rot2::IDENTITY, Pos2::ZERO).points(1.0);
+    for point in points {
+        assert!(rect.expand(EPSILON).contains(point), "{point:?}");
+    }
+}
+
+#[test]
+fn shape_is_a_closed_polygon_of_the_points() {
+    let rect = Rect::from_center_size(pos2(50.0, 50.0), vec2(20.0, 40.0));
+    let rotated = RotatedRect::new(rect, 4.0, Rot2::from_angle(0.3), pos2(50.0, 80.0));
+    let stroke = Stroke::new(1.0, Color32::RED);
+    let shape = rotated.to_shape(2.0, Color32::GOLD, stroke);
+    assert_eq!(shape.points, rotated.points(2.0));
+    assert!(shape.closed);
+    assert_eq!(shape.fill, Color32::GOLD);
+    assert_eq!(shape.stroke.width, stroke.width);
+}
+
+fn is_on_circle(point: Pos2, center: Pos2, radius: f32) -> bool {
+    (point.distance(center) - radius).abs() <= 1e-3
+}
+
+fn assert_points(actual: &[Pos2], expected: &[Pos2]) {
+    assert_eq!(actual.len(), expected.len(), "{actual:?}");
+    for (a, e) in actual.iter().zip(expected) {
+        assert!(a.distance(*e) <= EPSILON, "{actual:?} != {expected:?}");
+    }
+}
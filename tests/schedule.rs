@@ -0,0 +1,148 @@
+#![cfg(not(target_arch = "wasm32"))]
+
+use egui::Theme;
+use egui_theme_switch::system_theme::{Schedule, TimeOfDay};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Tromsø, Norway.
+const LATITUDE: f64 = 69.65;
+const LONGITUDE: f64 = 18.96;
+
+#[test]
+fn polar_day_is_light_without_switches() {
+    let schedule = Schedule::sun(LATITUDE, LONGITUDE, 60);
+    // The 21st of June 2024.
+    let midsummer = utc(1_718_928_000);
+    for hour in [0, 6, 12, 18, 23] {
+        let time = midsummer + hours(hour);
+        assert_eq!(schedule.theme_at(time), Theme::Light, "at {hour}:00");
+    }
+    assert_eq!(schedule.next_switch_after(midsummer), None);
+}
+
+#[test]
+fn polar_night_is_dark_without_switches() {
+    let schedule = Schedule::sun(LATITUDE, LONGITUDE, 60);
+    // The 21st of December 2024.
+    let midwinter = utc(1_734_739_200);
+    for hour in [0, 6, 12, 18, 23] {
+        let time = midwinter + hours(hour);
+        assert_eq!(schedule.theme_at(time), Theme::Dark, "at {hour}:00");
+    }
+    assert_eq!(schedule.next_switch_after(midwinter), None);
+}
+
+#[test]
+fn fixed_schedule_wraps_around_midnight() {
+    // Light at night, e.g. for a night shift.
+    let schedule = Schedule::fixed(TimeOfDay::new(20, 0), TimeOfDay::new(8, 0), 0);
+    let midnight = utc(1_718_928_000);
+    assert_eq!(schedule.theme_at(midnight + hours(23)), Theme::Light);
+    assert_eq!(schedule.theme_at(midnight + hours(3)), Theme::Light);
+    assert_eq!(schedule.theme_at(midnight + hours(8)), Theme::Dark);
+    assert_eq!(schedule.theme_at(midnight + hours(12)), Theme::Dark);
+    assert_eq!(schedule.theme_at(midnight + hours(20)), Theme::Light);
+
+    let next = schedule.next_switch_after(midnight + hours(23)).unwrap();
+    assert_eq!(next.theme, Theme::Dark);
+    assert_eq!(next.local_time, TimeOfDay::new(8, 0));
+    assert_eq!(next.at, midnight + hours(24 + 8));
+}
+
+#[test]
+fn changing_the_utc_offset_moves_fixed_times() {
+    // Midnight UTC is 09:00 in Japan.
+    let schedule = Schedule::fixed(TimeOfDay::new(7, 0), TimeOfDay::new(19, 0), 9 * 60);
+    let midnight = utc(1_718_928_000);
+    assert_eq!(schedule.theme_at(midnight), Theme::Light);
+    let next = schedule.next_switch_after(midnight).unwrap();
+    assert_eq!(next.at, midnight + hours(10));
+
+    // E.g. when daylight saving time ends, clones see the new offset as well.
+    let clone = schedule.clone();
+    schedule.set_utc_offset_minutes(0);
+    assert_eq!(clone.theme_at(midnight), Theme::Dark);
+    let next = clone.next_switch_after(midnight).unwrap();
+    assert_eq!((next.theme, next.at), (Theme::Light, midnight + hours(7)));
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn follow_system_tooltip_shows_the_next_switch() {
+    use egui::{CentralPanel, ThemePreference};
+    use egui_theme_switch::system_theme::{self, FakeClock, SystemThemeSource};
+    use egui_theme_switch::testing::{self, Harness};
+    use egui_theme_switch::ThemeSwitch;
+    use std::sync::Arc;
+
+    let clock = FakeClock::new(utc(1_718_928_000) + hours(12));
+    let schedule = Arc::new(
+        Schedule::fixed(TimeOfDay::new(7, 0), TimeOfDay::new(19, 30), 120)
+            .with_clock(clock.clone()),
+    );
+    let mut harness = Harness::new();
+    system_theme::register(harness.ctx(), schedule.clone());
+
+    let show = |harness: &mut Harness| {
+        harness.set_system_theme(schedule.system_theme());
+        harness.run(|ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                ui.add(ThemeSwitch::new(&mut ThemePreference::System));
+            });
+        });
+        let switch = testing::switches(harness.ctx()).remove(0);
+        let option = switch.option(&ThemePreference::System).unwrap();
+        option.hint.clone()
+    };
+    assert_eq!(
+        show(&mut harness).as_deref(),
+        Some("Switches to dark at 19:30")
+    );
+
+    clock.advance(hours(8));
+    assert_eq!(
+        show(&mut harness).as_deref(),
+        Some("Switches to light at 07:00")
+    );
+    assert_eq!(harness.ctx().theme(), Theme::Dark);
+}
+
+fn utc(unix_seconds: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(unix_seconds)
+}
+
+fn hours(hours: u64) -> Duration {
+    Duration::from_secs(hours * 3600)
+}
+
+#[cfg(feature = "testing")]
+#[test]
+fn next_switch_is_updated_below_a_source_with_an_opinion() {
+    use egui::{CentralPanel, ThemePreference};
+    use egui_theme_switch::system_theme::{self, FakeClock, ManualOverride, NextSwitch};
+    use egui_theme_switch::testing::Harness;
+    use egui_theme_switch::ThemeSwitch;
+    use system_theme::PriorityChain;
+
+    let clock = FakeClock::new(utc(1_718_928_000) + hours(12));
+    let schedule =
+        Schedule::fixed(TimeOfDay::new(7, 0), TimeOfDay::new(19, 30), 0).with_clock(clock.clone());
+    let manual = ManualOverride::new(Some(Theme::Dark));
+    let mut harness = Harness::new();
+    system_theme::register(
+        harness.ctx(),
+        PriorityChain::new().with(manual).with(schedule),
+    );
+
+    let mut show = || {
+        harness.run(|ctx| {
+            CentralPanel::default().show(ctx, |ui| {
+                ui.add(ThemeSwitch::new(&mut ThemePreference::System));
+            });
+        });
+        NextSwitch::load(harness.ctx()).map(|next| next.local_time)
+    };
+    assert_eq!(show(), Some(TimeOfDay::new(19, 30)));
+    clock.advance(hours(8));
+    assert_eq!(show(), Some(TimeOfDay::new(7, 0)));
+}
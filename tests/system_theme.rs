@@ -0,0 +1,130 @@
+use egui::{Color32, Context, RawInput, Theme, ThemePreference};
+use egui_theme_switch::system_theme::{
+    self, parse_gtk_theme, DesktopAppearance, FakeSource, ManualOverride, PriorityChain,
+    SystemThemeSource,
+};
+
+#[test]
+fn feed_reports_the_source_theme_to_egui() {
+    let ctx = Context::default();
+    let source = FakeSource::new(Some(Theme::Light));
+    run(&ctx, &source, Some(Theme::Dark));
+    assert_eq!(ctx.theme(), Theme::Light);
+
+    source.set(Some(Theme::Dark));
+    run(&ctx, &source, Some(Theme::Dark));
+    assert_eq!(ctx.theme(), Theme::Dark);
+    assert_eq!(source.queries(), 2);
+}
+
+#[test]
+fn feed_keeps_the_integration_theme_without_an_opinion() {
+    let ctx = Context::default();
+    let source = FakeSource::new(None);
+    run(&ctx, &source, Some(Theme::Light));
+    assert_eq!(ctx.theme(), Theme::Light);
+
+    run(&ctx, &source, None);
+    assert_eq!(ctx.theme(), ctx.options(|o| o.fallback_theme));
+}
+
+#[test]
+fn feed_doesnt_override_an_explicit_preference() {
+    let ctx = Context::default();
+    ctx.set_theme(ThemePreference::Light);
+    run(&ctx, &FakeSource::new(Some(Theme::Dark)), None);
+    assert_eq!(ctx.theme(), Theme::Light);
+}
+
+#[test]
+fn register_leaves_the_theme_alone() {
+    let ctx = Context::default();
+    let fallback = ctx.options(|o| o.fallback_theme);
+    let other = match fallback {
+        Theme::Dark => Theme::Light,
+        Theme::Light => Theme::Dark,
+    };
+    let source = FakeSource::new(Some(other));
+    system_theme::register(&ctx, source.clone());
+    let _ = ctx.run(RawInput::default(), |_| {});
+
+    assert_eq!(ctx.options(|o| o.fallback_theme), fallback);
+    assert_eq!(ctx.theme(), fallback);
+    assert_eq!(source.queries(), 0);
+}
+
+#[test]
+fn register_stores_the_desktop_appearance() {
+    let ctx = Context::default();
+    let appearance = DesktopAppearance {
+        accent_color: Some(Color32::from_rgb(0x35, 0x84, 0xe4)),
+        reduced_motion: true,
+        ..Default::default()
+    };
+    system_theme::register(&ctx, Appearance(appearance));
+    assert_eq!(DesktopAppearance::load(&ctx), DesktopAppearance::default());
+
+    let _ = ctx.run(RawInput::default(), |_| {});
+    assert_eq!(DesktopAppearance::load(&ctx), appearance);
+}
+
+#[test]
+fn priority_chain_uses_the_first_opinion() {
+    let manual = ManualOverride::default();
+    let fallback = FakeSource::new(Some(Theme::Dark));
+    let chain = PriorityChain::new()
+        .with(manual.clone())
+        .with(fallback.clone());
+    assert_eq!(chain.system_theme(), Some(Theme::Dark));
+    assert_eq!(fallback.queries(), 1);
+
+    manual.set(Some(Theme::Light));
+    assert_eq!(chain.system_theme(), Some(Theme::Light));
+    // Lower priority sources aren't asked once a theme is reported.
+    assert_eq!(fallback.queries(), 1);
+
+    manual.set(None);
+    fallback.set(None);
+    assert_eq!(chain.system_theme(), None);
+}
+
+#[test]
+fn gtk_theme_variant_wins_over_the_name() {
+    assert_eq!(parse_gtk_theme("Adwaita:dark"), Some(Theme::Dark));
+    assert_eq!(parse_gtk_theme("Adwaita-dark:light"), Some(Theme::Light));
+    assert_eq!(parse_gtk_theme("Adwaita: Dark "), Some(Theme::Dark));
+    // Unknown variants fall back to the name.
+    assert_eq!(parse_gtk_theme("Yaru-dark:compact"), Some(Theme::Dark));
+    assert_eq!(parse_gtk_theme("Yaru:compact"), Some(Theme::Light));
+}
+
+#[test]
+fn gtk_theme_name_decides_without_a_variant() {
+    assert_eq!(parse_gtk_theme("Adwaita-dark"), Some(Theme::Dark));
+    assert_eq!(parse_gtk_theme("'Breeze-Dark'"), Some(Theme::Dark));
+    assert_eq!(parse_gtk_theme("Adwaita"), Some(Theme::Light));
+    assert_eq!(parse_gtk_theme(""), None);
+    assert_eq!(parse_gtk_theme("  "), None);
+    assert_eq!(parse_gtk_theme(":dark"), None);
+}
+
+fn run(ctx: &Context, source: &FakeSource, integration_theme: Option<Theme>) {
+    let mut raw_input = RawInput {
+        system_theme: integration_theme,
+        ..Default::default()
+    };
+    system_theme::feed(&mut raw_input, source);
+    let _ = ctx.run(raw_input, |_| {});
+}
+
+struct Appearance(DesktopAppearance);
+
+impl SystemThemeSource for Appearance {
+    fn system_theme(&self) -> Option<Theme> {
+        None
+    }
+
+    fn desktop_appearance(&self) -> Option<DesktopAppearance> {
+        Some(self.0)
+    }
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "testing")]
+
+use egui::{CentralPanel, Context, Key, Modifiers, Response, Theme, ThemePreference};
+use egui_theme_switch::testing::{self, Harness};
+use egui_theme_switch::{GlobalThemeSwitch, ThemeSwitch};
+
+#[test]
+fn switches_record_their_options() {
+    let mut harness = Harness::new();
+    let mut first = ThemePreference::System;
+    let mut second = ThemePreference::Light;
+    let mut responses = Vec::new();
+    harness.run(|ctx| {
+        CentralPanel::default().show(ctx, |ui| {
+            responses.push(ui.add(ThemeSwitch::new(&mut first)));
+            responses.push(ui.add(ThemeSwitch::new(&mut second)));
+        });
+    });
+
+    let switches = testing::switches(harness.ctx());
+    assert_eq!(switches.len(), 2);
+    for (switch, response) in switches.iter().zip(&responses) {
+        assert_eq!(switch.id, response.id);
+        assert_eq!(switch.rect, response.rect);
+        let values: Vec<_> = switch.options.iter().map(|o| o.value).collect();
+        let labels: Vec<_> = switch.options.iter().map(|o| o.label).collect();
+        assert_eq!(
+            values,
+            [
+                ThemePreference::System,
+                ThemePreference::Dark,
+                ThemePreference::Light
+            ]
+        );
+        assert_eq!(labels, ["Follow System", "Dark", "Light"]);
+        for option in &switch.options {
+            assert!(switch.rect.contains_rect(option.rect));
+        }
+    }
+
+    let second_switch = testing::find_switch(harness.ctx(), responses[1].id);
+    assert_eq!(second_switch.as_ref(), Some(&switches[1]));
+}
+
+#[test]
+fn switches_are_forgotten_when_not_shown() {
+    let mut harness = Harness::new();
+    harness.run(|ctx| show_global_switch(ctx, &mut None));
+    assert_eq!(testing::switches(harness.ctx()).len(), 1);
+
+    harness.run(|_| {});
+    assert!(testing::switches(harness.ctx()).is_empty());
+}
+
+#[test]
+fn clicking_an_option_changes_the_theme() {
+    let mut harness = Harness::new();
+    let mut response = None;
+    harness.run(|ctx| show_global_switch(ctx, &mut response));
+
+    harness.click_option(ThemePreference::Dark);
+    harness.run(|ctx| show_global_switch(ctx, &mut response));
+    assert!(response.as_ref().is_some_and(Response::changed));
+    assert_eq!(
+        harness.ctx().options(|o| o.theme_preference),
+        ThemePreference::Dark
+    );
+    assert_eq!(harness.ctx().theme(), Theme::Dark);
+
+    // Clicking the selected option is not a change.
+    harness.click_option(ThemePreference::Dark);
+    harness.run(|ctx| show_global_switch(ctx, &mut response));
+    assert!(!response.as_ref().is_some_and(Response::changed));
+}
+
+#[test]
+fn tab_moves_the_focus_to_the_switch() {
+    let mut harness = Harness::new();
+    harness.run(|ctx| show_global_switch(ctx, &mut None));
+
+    harness.press_key(Key::Tab, Modifiers::NONE);
+    harness.run(|ctx| show_global_switch(ctx, &mut None));
+
+    let switch = &testing::switches(harness.ctx())[0];
+    let focused = harness.ctx().memory(|m| m.focused());
+    let focusable: Vec<_> = std::iter::once(switch.id)
+        .chain(switch.options.iter().map(|o| o.id))
+        .collect();
+    assert!(focused.is_some_and(|id| focusable.contains(&id)));
+}
+
+#[test]
+fn system_theme_is_simulated() {
+    let mut harness = Harness::new();
+    harness.set_system_theme(Some(Theme::Light));
+    harness.run(|ctx| show_global_switch(ctx, &mut None));
+    assert_eq!(harness.ctx().theme(), Theme::Light);
+
+    harness.set_system_theme(Some(Theme::Dark));
+    harness.run(|ctx| show_global_switch(ctx, &mut None));
+    assert_eq!(harness.ctx().theme(), Theme::Dark);
+
+    // An explicit preference wins over the system theme.
+    harness.click_option(ThemePreference::Light);
+    harness.run(|ctx| show_global_switch(ctx, &mut None));
+    assert_eq!(harness.ctx().theme(), Theme::Light);
+}
+
+fn show_global_switch(ctx: &Context, response: &mut Option<Response>) {
+    CentralPanel::default().show(ctx, |ui| {
+        *response = Some(ui.add(GlobalThemeSwitch::new()));
+    });
+}
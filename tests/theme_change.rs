@@ -0,0 +1,113 @@
+#![cfg(feature = "testing")]
+
+use egui::{CentralPanel, Context, Theme, ThemePreference};
+use egui_theme_switch::testing::Harness;
+use egui_theme_switch::theme_change::{self, Cause, ThemeChange};
+use egui_theme_switch::GlobalThemeSwitch;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn clicking_the_switch_is_a_user_change() {
+    let (mut harness, changes) = observed_harness();
+    harness.run(show_global_switch);
+
+    harness.click_option(ThemePreference::Dark);
+    harness.run(show_global_switch);
+    harness.run(show_global_switch);
+
+    assert_eq!(
+        *changes.lock().expect("not poisoned"),
+        [ThemeChange {
+            old_preference: ThemePreference::System,
+            new_preference: ThemePreference::Dark,
+            theme: Theme::Dark,
+            cause: Cause::User,
+        }]
+    );
+}
+
+#[test]
+fn system_theme_changes_are_reported_when_following_the_system() {
+    let (mut harness, changes) = observed_harness();
+    harness.set_system_theme(Some(Theme::Light));
+    harness.run(show_global_switch);
+    // The system theme that is reported on startup is not a change.
+    assert!(changes.lock().expect("not poisoned").is_empty());
+
+    harness.set_system_theme(Some(Theme::Dark));
+    harness.run(show_global_switch);
+    assert_eq!(
+        *changes.lock().expect("not poisoned"),
+        [ThemeChange {
+            old_preference: ThemePreference::System,
+            new_preference: ThemePreference::System,
+            theme: Theme::Dark,
+            cause: Cause::System,
+        }]
+    );
+
+    // With an explicit preference, the system theme doesn't matter.
+    harness.run(|ctx| ctx.set_theme(ThemePreference::Dark));
+    assert_eq!(changes.lock().expect("not poisoned").len(), 2);
+    harness.set_system_theme(Some(Theme::Light));
+    harness.run(show_global_switch);
+    assert_eq!(changes.lock().expect("not poisoned").len(), 2);
+}
+
+#[test]
+fn changes_by_the_app_are_programmatic() {
+    let (mut harness, changes) = observed_harness();
+    harness.run(|_| {});
+
+    harness.run(|ctx| ctx.set_theme(ThemePreference::Light));
+    // Changing the theme back and forth within one pass is not a change.
+    harness.run(|ctx| {
+        ctx.set_theme(ThemePreference::Dark);
+        ctx.set_theme(ThemePreference::Light);
+    });
+    harness.run(|ctx| theme_change::set_theme(ctx, ThemePreference::Dark, Cause::User));
+
+    let changes = changes.lock().expect("not poisoned");
+    let causes: Vec<_> = changes
+        .iter()
+        .map(|c| (c.new_preference, c.cause))
+        .collect();
+    assert_eq!(
+        causes,
+        [
+            (ThemePreference::Light, Cause::Programmatic),
+            (ThemePreference::Dark, Cause::User)
+        ]
+    );
+}
+
+#[test]
+fn every_observer_is_called() {
+    let mut harness = Harness::new();
+    let calls = Arc::new(Mutex::new(0));
+    for _ in 0..2 {
+        let calls = calls.clone();
+        theme_change::observe(harness.ctx(), move |_, _| {
+            *calls.lock().expect("not poisoned") += 1;
+        });
+    }
+    harness.run(|_| {});
+    harness.run(|ctx| ctx.set_theme(ThemePreference::Dark));
+    assert_eq!(*calls.lock().expect("not poisoned"), 2);
+}
+
+fn observed_harness() -> (Harness, Arc<Mutex<Vec<ThemeChange>>>) {
+    let harness = Harness::new();
+    let changes = Arc::new(Mutex::new(Vec::new()));
+    let sink = changes.clone();
+    theme_change::observe(harness.ctx(), move |_, change| {
+        sink.lock().expect("not poisoned").push(*change);
+    });
+    (harness, changes)
+}
+
+fn show_global_switch(ctx: &Context) {
+    CentralPanel::default().show(ctx, |ui| {
+        ui.add(GlobalThemeSwitch::new());
+    });
+}
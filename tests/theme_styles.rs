@@ -0,0 +1,110 @@
+#![cfg(feature = "testing")]
+
+use egui::{CentralPanel, Color32, Context, Style, Theme, ThemePreference};
+use egui_theme_switch::testing::Harness;
+use egui_theme_switch::{GlobalThemeSwitch, ThemeStyles, ThemeTransition};
+
+#[test]
+fn styles_are_installed_for_both_themes() {
+    let styles = branded_styles();
+    let mut harness = Harness::new();
+    harness.ctx().set_theme(ThemePreference::Dark);
+    harness.run(|ctx| show(ctx, &styles));
+    assert_eq!(harness.ctx().style_of(Theme::Dark), styles.dark);
+    assert_eq!(harness.ctx().style_of(Theme::Light), styles.light);
+
+    harness.click_option(ThemePreference::Light);
+    harness.run(|ctx| show(ctx, &styles));
+    assert_eq!(harness.ctx().theme(), Theme::Light);
+    assert_eq!(harness.ctx().style(), styles.light);
+}
+
+#[test]
+fn stock_styles_are_tweaked() {
+    let styles = branded_styles();
+    for theme in [Theme::Dark, Theme::Light] {
+        let mut expected = theme.default_visuals();
+        expected.selection.bg_fill = brand_color(theme);
+        assert_eq!(styles.style(theme).visuals, expected);
+    }
+}
+
+#[test]
+fn later_changes_to_the_style_are_kept() {
+    let styles = branded_styles();
+    let mut harness = Harness::new();
+    harness.run(|ctx| show(ctx, &styles));
+
+    harness
+        .ctx()
+        .style_mut_of(Theme::Dark, |style| style.visuals.panel_fill = Color32::RED);
+    harness.run(|ctx| show(ctx, &styles));
+    let style = harness.ctx().style_of(Theme::Dark);
+    assert_eq!(style.visuals.panel_fill, Color32::RED);
+
+    // Other styles replace the modified ones.
+    let plain = ThemeStyles::new(Style::default(), Theme::Light.default_style());
+    harness.run(|ctx| show(ctx, &plain));
+    assert_eq!(harness.ctx().style_of(Theme::Dark), plain.dark);
+}
+
+#[test]
+fn styles_created_every_pass_are_installed_once() {
+    let mut harness = Harness::new();
+    harness.run(|ctx| show(ctx, &branded_styles()));
+
+    harness
+        .ctx()
+        .style_mut_of(Theme::Dark, |style| style.visuals.panel_fill = Color32::RED);
+    harness.run(|ctx| show(ctx, &branded_styles()));
+    let style = harness.ctx().style_of(Theme::Dark);
+    assert_eq!(style.visuals.panel_fill, Color32::RED);
+}
+
+#[test]
+fn styles_installed_during_a_cross_fade_are_kept() {
+    let show = |ctx: &Context, styles: &ThemeStyles| {
+        CentralPanel::default().show(ctx, |ui| {
+            ui.add(
+                GlobalThemeSwitch::new()
+                    .styles(styles.clone())
+                    .transition(ThemeTransition::cross_fade(0.5)),
+            );
+        });
+    };
+    let styles = branded_styles();
+    let mut harness = Harness::new();
+    harness.ctx().set_theme(ThemePreference::Light);
+    harness.run(|ctx| show(ctx, &styles));
+
+    harness.click_option(ThemePreference::Dark);
+    harness.run(|ctx| show(ctx, &styles));
+    harness.run(|ctx| show(ctx, &styles));
+
+    let plain = ThemeStyles::new(Style::default(), Theme::Light.default_style());
+    for _ in 0..60 {
+        harness.run(|ctx| show(ctx, &plain));
+    }
+    assert_eq!(
+        harness.ctx().style_of(Theme::Dark).visuals,
+        plain.dark.visuals
+    );
+    assert_eq!(harness.ctx().style().visuals, plain.dark.visuals);
+}
+
+fn branded_styles() -> ThemeStyles {
+    ThemeStyles::from_stock(|theme, style| style.visuals.selection.bg_fill = brand_color(theme))
+}
+
+fn brand_color(theme: Theme) -> Color32 {
+    match theme {
+        Theme::Dark => Color32::from_rgb(0x9d, 0x4e, 0xdd),
+        Theme::Light => Color32::from_rgb(0x5a, 0x18, 0x9a),
+    }
+}
+
+fn show(ctx: &Context, styles: &ThemeStyles) {
+    CentralPanel::default().show(ctx, |ui| {
+        ui.add(GlobalThemeSwitch::new().styles(styles.clone()));
+    });
+}
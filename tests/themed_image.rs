@@ -0,0 +1,103 @@
+#![cfg(feature = "testing")]
+
+use egui::emath::vec2;
+use egui::epaint::{ClippedShape, Shape, TextureId};
+use egui::load::SizedTexture;
+use egui::{CentralPanel, Context, FullOutput, Image, Theme, ThemePreference};
+use egui_theme_switch::testing::Harness;
+use egui_theme_switch::ThemedImage;
+
+const DARK: TextureId = TextureId::User(1);
+const LIGHT: TextureId = TextureId::User(2);
+
+#[test]
+fn shows_the_variant_for_the_current_theme() {
+    let mut harness = Harness::new();
+    harness.ctx().set_theme(ThemePreference::Dark);
+    let output = harness.run(|ctx| show(ctx, None));
+    assert_eq!(textures(&output), [(DARK, 255)]);
+
+    harness.ctx().set_theme(ThemePreference::Light);
+    let output = harness.run(|ctx| show(ctx, None));
+    assert_eq!(textures(&output), [(LIGHT, 255)]);
+
+    let image = themed_image();
+    assert!(std::ptr::eq(
+        image.current(harness.ctx()),
+        image.image(Theme::Light)
+    ));
+}
+
+#[test]
+fn cross_fades_between_variants() {
+    let mut harness = Harness::new();
+    harness.ctx().set_theme(ThemePreference::Dark);
+    // The first time the image is shown, it doesn't fade in.
+    let output = harness.run(|ctx| show(ctx, Some(0.1)));
+    assert_eq!(textures(&output), [(DARK, 255)]);
+
+    harness.ctx().set_theme(ThemePreference::Light);
+    harness.run(|ctx| show(ctx, Some(0.1)));
+    let output = harness.run(|ctx| show(ctx, Some(0.1)));
+    // The outgoing variant stays opaque underneath the incoming one,
+    // so that together they cover the background fully.
+    let fading = textures(&output);
+    assert_eq!(fading.len(), 2);
+    assert_eq!(fading[0], (DARK, 255));
+    assert_eq!(fading[1].0, LIGHT);
+    assert!(fading[1].1 > 0 && fading[1].1 < 255);
+    let uncovered = fading
+        .iter()
+        .map(|(_, alpha)| 1.0 - f32::from(*alpha) / 255.0)
+        .product::<f32>();
+    assert_eq!(uncovered, 0.0);
+
+    for _ in 0..10 {
+        harness.run(|ctx| show(ctx, Some(0.1)));
+    }
+    let output = harness.run(|ctx| show(ctx, Some(0.1)));
+    assert_eq!(textures(&output), [(LIGHT, 255)]);
+}
+
+#[test]
+fn cross_fade_is_skipped_without_animations() {
+    let mut harness = Harness::new();
+    harness.ctx().set_theme(ThemePreference::Dark);
+    harness.ctx().style_mut(|style| style.animation_time = 0.0);
+    harness.run(|ctx| show(ctx, Some(0.1)));
+
+    harness.ctx().set_theme(ThemePreference::Light);
+    harness.ctx().style_mut(|style| style.animation_time = 0.0);
+    let output = harness.run(|ctx| show(ctx, Some(0.1)));
+    assert_eq!(textures(&output), [(LIGHT, 255)]);
+}
+
+fn themed_image() -> ThemedImage<'static> {
+    let texture = |id| Image::from_texture(SizedTexture::new(id, vec2(32.0, 16.0)));
+    ThemedImage::new(texture(DARK), texture(LIGHT))
+}
+
+fn show(ctx: &Context, cross_fade: Option<f32>) {
+    CentralPanel::default().show(ctx, |ui| {
+        let image = themed_image();
+        match cross_fade {
+            Some(duration) => ui.add(image.cross_fade(duration)),
+            None => ui.add(image),
+        };
+    });
+}
+
+/// The textured shapes in paint order with their opacity.
+fn textures(output: &FullOutput) -> Vec<(TextureId, u8)> {
+    output
+        .shapes
+        .iter()
+        .filter_map(|ClippedShape { shape, .. }| match shape {
+            Shape::Rect(rect) => rect
+                .brush
+                .as_ref()
+                .map(|b| (b.fill_texture_id, rect.fill.a())),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,158 @@
+#![cfg(feature = "testing")]
+
+use egui::epaint::{ClippedShape, Color32, Shape};
+use egui::{CentralPanel, Context, FullOutput, Theme, ThemePreference, Visuals};
+use egui_theme_switch::system_theme::DesktopAppearance;
+use egui_theme_switch::testing::Harness;
+use egui_theme_switch::{GlobalThemeSwitch, ThemeTransition};
+
+const DURATION: f32 = 0.5;
+
+#[test]
+fn cross_fade_restores_the_style_exactly() {
+    let mut harness = light_harness();
+    harness.run(show);
+    let original = harness.ctx().style_of(Theme::Dark);
+
+    harness.click_option(ThemePreference::Dark);
+    harness.run(show);
+    harness.run(show);
+    let fading = harness.ctx().style().visuals.clone();
+    assert_ne!(fading, Visuals::light());
+    assert_ne!(fading, Visuals::dark());
+
+    run_for(&mut harness, DURATION);
+    assert_eq!(harness.ctx().style_of(Theme::Dark), original);
+    assert_eq!(
+        harness.ctx().style_of(Theme::Light).visuals,
+        Visuals::light()
+    );
+}
+
+#[test]
+fn cross_fade_ends_when_the_theme_changes_by_other_means() {
+    let mut harness = light_harness();
+    harness.run(show);
+    let original = harness.ctx().style_of(Theme::Dark);
+
+    harness.click_option(ThemePreference::Dark);
+    harness.run(show);
+    harness.run(show);
+
+    // E.g. the app changes the preference.
+    harness.ctx().set_theme(ThemePreference::Light);
+    harness.run(show);
+    assert_eq!(harness.ctx().style_of(Theme::Dark), original);
+    assert_eq!(harness.ctx().style().visuals, Visuals::light());
+}
+
+#[test]
+fn cross_fade_ends_when_interrupted_by_a_click() {
+    let mut harness = light_harness();
+    harness.run(show);
+    let original_dark = harness.ctx().style_of(Theme::Dark);
+    let original_light = harness.ctx().style_of(Theme::Light);
+
+    harness.click_option(ThemePreference::Dark);
+    harness.run(show);
+    harness.run(show);
+
+    harness.click_option(ThemePreference::Light);
+    harness.run(show);
+    assert_eq!(harness.ctx().theme(), Theme::Light);
+    assert_eq!(harness.ctx().style_of(Theme::Dark), original_dark);
+
+    // The second cross-fade starts where the first one was interrupted.
+    let fading = harness.ctx().style().visuals.clone();
+    assert_ne!(fading, Visuals::light());
+    assert_ne!(fading, Visuals::dark());
+
+    run_for(&mut harness, DURATION);
+    assert_eq!(harness.ctx().style_of(Theme::Light), original_light);
+}
+
+#[test]
+fn circular_reveal_covers_the_screen_until_it_ends() {
+    let mut harness = light_harness();
+    harness.run(show_reveal);
+
+    harness.click_option(ThemePreference::Dark);
+    let output = harness.run(show_reveal);
+    assert!(covers_with(&output, Visuals::light().panel_fill));
+
+    for _ in 0..(DURATION * 60.0).ceil() as usize {
+        harness.run(show_reveal);
+    }
+    let output = harness.run(show_reveal);
+    assert!(!covers_with(&output, Visuals::light().panel_fill));
+}
+
+#[test]
+fn transitions_are_skipped_for_reduced_motion() {
+    let mut harness = light_harness();
+    DesktopAppearance {
+        reduced_motion: true,
+        ..Default::default()
+    }
+    .store(harness.ctx());
+    harness.run(show_reveal);
+
+    harness.click_option(ThemePreference::Dark);
+    let output = harness.run(show_reveal);
+    assert_eq!(harness.ctx().theme(), Theme::Dark);
+    assert!(!covers_with(&output, Visuals::light().panel_fill));
+
+    harness.click_option(ThemePreference::Light);
+    harness.run(show);
+    assert_eq!(harness.ctx().style().visuals, Visuals::light());
+}
+
+#[test]
+fn transitions_are_skipped_without_animations() {
+    let mut harness = light_harness();
+    harness
+        .ctx()
+        .all_styles_mut(|style| style.animation_time = 0.0);
+    harness.run(show);
+
+    harness.click_option(ThemePreference::Dark);
+    harness.run(show);
+    harness.run(show);
+    assert_eq!(harness.ctx().style().visuals, Visuals::dark());
+}
+
+fn light_harness() -> Harness {
+    let harness = Harness::new();
+    harness.ctx().set_theme(ThemePreference::Light);
+    harness
+}
+
+/// Runs enough passes for `seconds` to pass.
+fn run_for(harness: &mut Harness, seconds: f32) {
+    for _ in 0..=(seconds * 60.0).ceil() as usize {
+        harness.run(show);
+    }
+}
+
+fn show(ctx: &Context) {
+    CentralPanel::default().show(ctx, |ui| {
+        ui.add(GlobalThemeSwitch::new().transition(ThemeTransition::cross_fade(DURATION)));
+    });
+}
+
+fn show_reveal(ctx: &Context) {
+    CentralPanel::default().show(ctx, |ui| {
+        ui.add(GlobalThemeSwitch::new().transition(ThemeTransition::circular_reveal(DURATION)));
+    });
+}
+
+/// Whether a mesh in the given colour is painted, i.e. the reveal's cover.
+fn covers_with(output: &FullOutput, color: Color32) -> bool {
+    output
+        .shapes
+        .iter()
+        .any(|ClippedShape { shape, .. }| match shape {
+            Shape::Mesh(mesh) => mesh.vertices.iter().any(|v| v.color == color),
+            _ => false,
+        })
+}
@@ -0,0 +1,175 @@
+//! Tests the `XdgPortal` source against a stand-in portal on a private session bus.
+//! The test is skipped when `dbus-daemon` is not installed, except on CI where it fails instead.
+
+#![cfg(all(feature = "xdg-portal", target_os = "linux"))]
+
+use egui::{Color32, Theme};
+use egui_theme_switch::system_theme::{
+    Contrast, DesktopAppearance, SystemThemeSource, WatcherStatus, XdgPortal,
+};
+use futures_lite::future::block_on;
+use std::io::{BufRead as _, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use zbus::object_server::SignalContext;
+use zbus::zvariant::{OwnedValue, Value};
+use zbus::{fdo, interface, Connection};
+
+const NAMESPACE: &str = "org.freedesktop.appearance";
+const PATH: &str = "/org/freedesktop/portal/desktop";
+
+#[test]
+fn reads_and_follows_appearance_settings() {
+    let Some(bus) = SessionBus::start() else {
+        assert!(
+            std::env::var_os("CI").is_none(),
+            "dbus-daemon is required on CI"
+        );
+        eprintln!("dbus-daemon is not available, skipping");
+        return;
+    };
+    // The portal uses the session bus from the environment.
+    std::env::set_var("DBUS_SESSION_BUS_ADDRESS", &bus.address);
+
+    let connection = block_on(serve_stand_in_portal(StandInSettings {
+        color_scheme: 1,
+        accent_color: (1.0, 0.0, 0.0),
+        contrast: 0,
+    }));
+
+    let portal = XdgPortal::new();
+    wait_for(|| portal.status() == WatcherStatus::Active);
+    wait_for(|| portal.system_theme() == Some(Theme::Dark));
+    wait_for(|| {
+        portal.desktop_appearance()
+            == Some(DesktopAppearance {
+                accent_color: Some(Color32::from_rgb(255, 0, 0)),
+                contrast: Contrast::Normal,
+                reduced_motion: false,
+            })
+    });
+
+    block_on(async {
+        let iface = connection
+            .object_server()
+            .interface::<_, StandInSettings>(PATH)
+            .await
+            .expect("stand-in portal is served");
+        let mut settings = iface.get_mut().await;
+        settings.color_scheme = 2;
+        settings.contrast = 1;
+        settings.accent_color = (-1.0, -1.0, -1.0);
+        StandInSettings::setting_changed(
+            iface.signal_context(),
+            NAMESPACE,
+            "contrast",
+            1u32.into(),
+        )
+        .await
+        .expect("signal is emitted");
+    });
+
+    wait_for(|| portal.system_theme() == Some(Theme::Light));
+    wait_for(|| {
+        portal.desktop_appearance()
+            == Some(DesktopAppearance {
+                accent_color: None,
+                contrast: Contrast::High,
+                reduced_motion: false,
+            })
+    });
+
+    // The watcher reconnects when the portal is restarted.
+    drop(connection);
+    wait_for(|| portal.status() != WatcherStatus::Active);
+    let _connection = block_on(serve_stand_in_portal(StandInSettings {
+        color_scheme: 1,
+        accent_color: (0.0, 0.0, 1.0),
+        contrast: 0,
+    }));
+    wait_for(|| portal.status() == WatcherStatus::Active);
+    wait_for(|| portal.system_theme() == Some(Theme::Dark));
+}
+
+struct StandInSettings {
+    color_scheme: u32,
+    accent_color: (f64, f64, f64),
+    contrast: u32,
+}
+
+#[interface(name = "org.freedesktop.portal.Settings")]
+impl StandInSettings {
+    fn read(&self, namespace: &str, key: &str) -> fdo::Result<OwnedValue> {
+        let value = match (namespace, key) {
+            (NAMESPACE, "color-scheme") => Value::from(self.color_scheme),
+            (NAMESPACE, "accent-color") => Value::from(self.accent_color),
+            (NAMESPACE, "contrast") => Value::from(self.contrast),
+            _ => return Err(fdo::Error::Failed(format!("unknown setting {key}"))),
+        };
+        value
+            .try_to_owned()
+            .map_err(|e| fdo::Error::Failed(e.to_string()))
+    }
+
+    #[zbus(property)]
+    fn version(&self) -> u32 {
+        2
+    }
+
+    #[zbus(signal)]
+    async fn setting_changed(
+        context: &SignalContext<'_>,
+        namespace: &str,
+        key: &str,
+        value: Value<'_>,
+    ) -> zbus::Result<()>;
+}
+
+async fn serve_stand_in_portal(settings: StandInSettings) -> Connection {
+    zbus::connection::Builder::session()
+        .and_then(|b| b.name("org.freedesktop.portal.Desktop"))
+        .and_then(|b| b.serve_at(PATH, settings))
+        .expect("valid connection settings")
+        .build()
+        .await
+        .expect("connected to session bus")
+}
+
+fn wait_for(mut condition: impl FnMut() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !condition() {
+        assert!(Instant::now() < deadline, "timed out");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+struct SessionBus {
+    daemon: Child,
+    address: String,
+}
+
+impl SessionBus {
+    fn start() -> Option<Self> {
+        let mut daemon = Command::new("dbus-daemon")
+            .args(["--session", "--nofork", "--print-address=1"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .ok()?;
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take()?)
+            .read_line(&mut address)
+            .ok()?;
+        Some(Self {
+            daemon,
+            address: address.trim().to_owned(),
+        })
+    }
+}
+
+impl Drop for SessionBus {
+    fn drop(&mut self) {
+        _ = self.daemon.kill();
+        _ = self.daemon.wait();
+    }
+}
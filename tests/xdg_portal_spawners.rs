@@ -0,0 +1,77 @@
+//! Tests that the runtime adapters of `XdgPortal` run the watcher on that runtime.
+//! The session bus can't be reached, so the watcher reports an error
+//! (and requests a repaint) as soon as it runs.
+
+#![cfg(all(any(feature = "tokio", feature = "async-std"), target_os = "linux"))]
+
+use egui::Context;
+use egui_theme_switch::system_theme::{SystemThemeSource, XdgPortal};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+const UNREACHABLE_BUS: &str = "unix:path=/nonexistent/egui-theme-switch-bus";
+
+#[cfg(feature = "tokio")]
+#[test]
+fn tokio_runs_the_watcher() {
+    use egui_theme_switch::system_theme::WatcherStatus;
+
+    std::env::set_var("DBUS_SESSION_BUS_ADDRESS", UNREACHABLE_BUS);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let (ctx, repainted_on) = context();
+
+    let portal = XdgPortal::spawn_on_tokio(runtime.handle());
+    portal.attach(&ctx);
+    // A current thread runtime only runs its tasks inside of `block_on`.
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(portal.status(), WatcherStatus::Connecting);
+
+    runtime.block_on(async {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while portal.status() == WatcherStatus::Connecting {
+            assert!(Instant::now() < deadline, "timed out");
+            futures_lite::future::yield_now().await;
+        }
+    });
+    let repainted_on = repainted_on.lock().unwrap().clone();
+    assert_eq!(
+        repainted_on.map(|t| t.id()),
+        Some(thread::current().id()),
+        "the watcher didn't run on the runtime"
+    );
+}
+
+#[cfg(feature = "async-std")]
+#[test]
+fn async_std_runs_the_watcher() {
+    std::env::set_var("DBUS_SESSION_BUS_ADDRESS", UNREACHABLE_BUS);
+    let (ctx, repainted_on) = context();
+
+    let portal = XdgPortal::spawn_on_async_std();
+    portal.attach(&ctx);
+    // The watcher may have failed before it was attached,
+    // it requests another repaint when it retries.
+    let deadline = Instant::now() + Duration::from_secs(10);
+    let thread = loop {
+        if let Some(thread) = repainted_on.lock().unwrap().clone() {
+            break thread;
+        }
+        assert!(Instant::now() < deadline, "timed out");
+        thread::sleep(Duration::from_millis(10));
+    };
+    assert_eq!(thread.name(), Some("async-std/runtime"));
+}
+
+/// A context that remembers the thread that last requested a repaint.
+fn context() -> (Context, Arc<Mutex<Option<Thread>>>) {
+    let ctx = Context::default();
+    let repainted_on = Arc::new(Mutex::new(None));
+    ctx.set_request_repaint_callback({
+        let repainted_on = repainted_on.clone();
+        move |_| *repainted_on.lock().unwrap() = Some(thread::current())
+    });
+    (ctx, repainted_on)
+}